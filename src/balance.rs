@@ -0,0 +1,199 @@
+//! Evening out loads by moving items between bins, such as snacks between elves.
+//!
+//! Finding the best arrangement is bin packing, which is too slow to do exactly, so
+//! [`rebalance`] improves things greedily instead: each move is the single item move that helps
+//! most, until no move helps or the moves run out.
+
+use color_eyre::{eyre::eyre, Report, Result};
+use std::str::FromStr;
+
+/// What a rebalance tries to make as small as it can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    /// The heaviest bin's load.
+    Max,
+    /// The difference between the heaviest and lightest bins' loads.
+    Spread,
+}
+
+impl FromStr for Goal {
+    type Err = Report;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "max" => Ok(Goal::Max),
+            "spread" => Ok(Goal::Spread),
+            _ => Err(eyre!("Goal must be max or spread, not {s}")),
+        }
+    }
+}
+
+/// One item moving from one bin to another, by their indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub item: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// The moves to make, in order, and each bin's load before and after them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub moves: Vec<Move>,
+    pub before: Vec<usize>,
+    pub after: Vec<usize>,
+}
+
+impl Plan {
+    /// The heaviest load before and after the moves.
+    pub fn max(&self) -> (usize, usize) {
+        (max(&self.before), max(&self.after))
+    }
+
+    /// The spread of the loads before and after the moves.
+    pub fn spread(&self) -> (usize, usize) {
+        (spread(&self.before), spread(&self.after))
+    }
+}
+
+fn max(loads: &[usize]) -> usize {
+    loads.iter().copied().max().unwrap_or(0)
+}
+
+fn spread(loads: &[usize]) -> usize {
+    max(loads) - loads.iter().copied().min().unwrap_or(0)
+}
+
+/// How good the loads are after a move, lower being better: the goal first, then the other
+/// measure, then how much the sum of the loads' squares changed, which falls with any move from a
+/// heavier bin to a lighter one that doesn't overshoot, so ties at the top still make progress.
+type Score = (usize, usize, i128);
+
+fn score(goal: Goal, max: usize, min: usize, squares: i128) -> Score {
+    match goal {
+        Goal::Max => (max, max - min, squares),
+        Goal::Spread => (max - min, max, squares),
+    }
+}
+
+/// Moves at most `max_moves` items between `bins`, each a list of item weights, to bring down
+/// `goal`.
+pub fn rebalance(bins: &[Vec<usize>], goal: Goal, max_moves: usize) -> Plan {
+    let mut bins = bins.to_vec();
+    let mut loads: Vec<usize> = bins.iter().map(|bin| bin.iter().sum()).collect();
+    let before = loads.clone();
+    let mut moves = Vec::new();
+
+    while moves.len() < max_moves && bins.len() > 1 {
+        let mut order: Vec<usize> = (0..bins.len()).collect();
+        order.sort_by_key(|&idx| loads[idx]);
+        let (lightest, heaviest) = (order[0], order[order.len() - 1]);
+        // The heaviest and lightest loads of the bins a move leaves alone.
+        let untouched = |from: usize, to: usize| {
+            let mut others = order.iter().filter(|&&idx| idx != from && idx != to);
+            let low = others.next().map(|&idx| loads[idx]);
+            let high = others.next_back().map(|&idx| loads[idx]).or(low);
+            (low, high)
+        };
+
+        // Only moves from the heaviest bin or to the lightest can change either measure.
+        let candidates = bins[heaviest]
+            .iter()
+            .enumerate()
+            .flat_map(|(slot, &item)| (0..bins.len()).map(move |to| (heaviest, slot, item, to)))
+            .chain(bins.iter().enumerate().flat_map(|(from, bin)| {
+                bin.iter()
+                    .enumerate()
+                    .map(move |(slot, &item)| (from, slot, item, lightest))
+            }))
+            .filter(|&(from, _, item, to)| from != to && item > 0);
+
+        let mut best: Option<(Score, usize, usize, usize)> = None;
+        for (from, slot, item, to) in candidates {
+            let (from_load, to_load) = (loads[from] - item, loads[to] + item);
+            let (low, high) = untouched(from, to);
+            let max = high.map_or(from_load, |h| h.max(from_load)).max(to_load);
+            let min = low.map_or(from_load, |l| l.min(from_load)).min(to_load);
+            let squares = 2 * item as i128 * (item as i128 - (loads[from] - loads[to]) as i128);
+            let score = score(goal, max, min, squares);
+            if best.is_none_or(|(best, ..)| score < best) {
+                best = Some((score, from, slot, to));
+            }
+        }
+
+        let now = score(goal, max(&loads), max(&loads) - spread(&loads), 0);
+        match best {
+            Some((score, from, slot, to)) if score < now => {
+                let item = bins[from].swap_remove(slot);
+                bins[to].push(item);
+                loads[from] -= item;
+                loads[to] += item;
+                moves.push(Move { item, from, to });
+            }
+            _ => break,
+        }
+    }
+
+    Plan {
+        moves,
+        before,
+        after: loads,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn evens_out_two_bins() {
+        let plan = rebalance(&[vec![5, 5], vec![]], Goal::Max, 10);
+        assert_eq!(
+            vec![Move {
+                item: 5,
+                from: 0,
+                to: 1
+            }],
+            plan.moves
+        );
+        assert_eq!(vec![5, 5], plan.after);
+    }
+
+    #[test]
+    fn stops_at_the_move_limit() {
+        let bins = [vec![1, 1, 1, 1], vec![], vec![]];
+        assert_eq!(vec![3, 1, 0], rebalance(&bins, Goal::Max, 1).after);
+        assert_eq!(vec![2, 1, 1], rebalance(&bins, Goal::Max, 2).after);
+        assert_eq!(2, rebalance(&bins, Goal::Max, 10).moves.len());
+    }
+
+    #[test]
+    fn leaves_balanced_bins_alone() {
+        assert!(rebalance(&[vec![4], vec![3]], Goal::Spread, 5)
+            .moves
+            .is_empty());
+        assert!(rebalance(&[vec![4, 1]], Goal::Max, 5).moves.is_empty());
+        assert!(rebalance(&[], Goal::Max, 5).moves.is_empty());
+    }
+
+    #[test]
+    fn works_through_ties_at_the_top() {
+        let plan = rebalance(&[vec![2, 2, 2], vec![2, 2, 2], vec![]], Goal::Max, 10);
+        assert_eq!((6, 4), plan.max());
+        assert_eq!((6, 0), plan.spread());
+    }
+
+    #[test]
+    fn spread_can_move_from_the_middle() {
+        // The heaviest bin's only item is too big to help, but raising the lightest still does.
+        let plan = rebalance(&[vec![10], vec![4, 2], vec![]], Goal::Spread, 1);
+        assert_eq!(
+            vec![Move {
+                item: 4,
+                from: 1,
+                to: 2
+            }],
+            plan.moves
+        );
+        assert_eq!((10, 8), plan.spread());
+    }
+}
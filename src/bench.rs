@@ -0,0 +1,108 @@
+//! Timing each step of a day's solution over many runs.
+
+use color_eyre::Result;
+use std::time::Duration;
+
+use crate::json::Json;
+use crate::runner::{Day, Part};
+
+/// Summary of how long a step took over several runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub mean: Duration,
+    pub min: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Summarises the times taken by each run.  Panics if there are no samples.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let runs = samples.len();
+        let nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / runs as f64;
+        let variance = nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / runs as f64;
+        Self {
+            runs,
+            mean: Duration::from_nanos(mean.round() as u64),
+            min: *samples.iter().min().expect("at least one sample"),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        Json::object()
+            .with("mean_ns", self.mean.as_nanos())
+            .with("min_ns", self.min.as_nanos())
+            .with("stddev_ns", self.stddev.as_nanos())
+    }
+}
+
+/// Timings for each step of one day.  A part is `None` if it wasn't run or it fails.
+#[derive(Debug, Clone)]
+pub struct Bench {
+    pub day: u8,
+    pub parse: Stats,
+    pub part1: Option<Stats>,
+    pub part2: Option<Stats>,
+}
+
+impl Bench {
+    pub fn to_json(&self) -> Json {
+        Json::object()
+            .with("day", self.day)
+            .with("runs", self.parse.runs)
+            .with("parse", self.parse.to_json())
+            .with(
+                "part1",
+                self.part1.as_ref().map_or(Json::Null, Stats::to_json),
+            )
+            .with(
+                "part2",
+                self.part2.as_ref().map_or(Json::Null, Stats::to_json),
+            )
+    }
+}
+
+/// Solves `input` with `day` once to warm up, and then `runs` more times (at least once), timing
+/// each step.
+pub fn bench(day: &Day, input: &str, part: Part, runs: usize) -> Result<Bench> {
+    let warmup = (day.run)(input, part)?;
+    let part1_ok = warmup.part1.as_ref().is_some_and(|p| p.answer.is_ok());
+    let part2_ok = warmup.part2.as_ref().is_some_and(|p| p.answer.is_ok());
+
+    let mut parse = Vec::with_capacity(runs);
+    let mut part1 = Vec::with_capacity(runs);
+    let mut part2 = Vec::with_capacity(runs);
+    for _ in 0..runs.max(1) {
+        let run = (day.run)(input, part)?;
+        parse.push(run.parse_time);
+        part1.extend(run.part1.map(|p| p.time));
+        part2.extend(run.part2.map(|p| p.time));
+    }
+
+    Ok(Bench {
+        day: day.day,
+        parse: Stats::from_samples(&parse),
+        part1: part1_ok.then(|| Stats::from_samples(&part1)),
+        part2: part2_ok.then(|| Stats::from_samples(&part2)),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stats_from_samples() {
+        let samples: Vec<_> = [2, 4, 4, 4, 5, 5, 7, 9]
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(8, stats.runs);
+        assert_eq!(Duration::from_millis(5), stats.mean);
+        assert_eq!(Duration::from_millis(2), stats.min);
+        assert_eq!(Duration::from_millis(2), stats.stddev);
+    }
+}
@@ -0,0 +1,369 @@
+use advent2022::bench::{self, Stats};
+use advent2022::gen::{self, Rng};
+use advent2022::input;
+use advent2022::json::Json;
+use advent2022::log::{self, Level};
+use advent2022::parse;
+use advent2022::runner::{self, Day, Part, Run};
+use advent2022::verify::{self, Expected, Outcome};
+use color_eyre::{eyre::eyre, Result};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+const USAGE: &str = "Usage: aoc [-q | -v | -vv] [--day N] [--part 1|2|both] [--input PATH|-]
+       aoc verify [-q | -v | -vv] [--answers PATH]
+       aoc bench [-q | -v | -vv] [--day N] [--part 1|2|both] [--input PATH|-] [--runs N] [--json]
+       aoc gen --day N [--size N] [--seed N] [--answers]
+
+Solves the puzzle for one day, or every day in turn if no day is given, and prints a
+summary of the answers and how long they took.
+
+With verify, solves every input listed in an answers file and checks the answers match.
+
+With bench, solves each day many times and reports how long parsing and each part took.
+
+With gen, writes a random input for a day to stdout, and with --answers its expected answers to
+stderr, worked out independently of the solution, as lines for an answers file.
+
+Options:
+  -d, --day N         Only solve day N
+  -p, --part PART     Solve part 1, part 2 or both (default: both)
+  -i, --input PATH    Read the input from PATH, or from stdin if PATH is '-'
+                      (default: dayN.txt in $AOC_INPUT_DIR, or the current directory)
+  -a, --answers PATH  The answers file to verify against
+                      (default: answers.txt in $AOC_INPUT_DIR, or the current directory)
+  -r, --runs N        How many times to solve each day when benchmarking (default: 10)
+      --json          Write the benchmark results as JSON
+  -s, --size N        Roughly how many items to generate (default: 100)
+      --seed N        Generate the input from this seed (default: the time, shown on stderr)
+      --answers       Write the generated input's answers to stderr
+  -q, --quiet         Only print the answers, without timings or totals
+  -v, --verbose       Explain how the answers were found, twice to show every step
+  -h, --help          Show this help";
+
+enum Command {
+    Solve,
+    Verify,
+    Bench,
+    Gen,
+}
+
+struct Args {
+    command: Command,
+    day: Option<u8>,
+    part: Part,
+    input: Option<String>,
+    answers: Option<PathBuf>,
+    runs: usize,
+    json: bool,
+    size: usize,
+    seed: Option<u64>,
+    gen_answers: bool,
+}
+
+fn parse_args() -> Result<Option<Args>> {
+    let mut args = Args {
+        command: Command::Solve,
+        day: None,
+        part: Part::Both,
+        input: None,
+        answers: None,
+        runs: 10,
+        json: false,
+        size: gen::DEFAULT_SIZE,
+        seed: None,
+        gen_answers: false,
+    };
+    let mut quiet = false;
+    let mut verbose = 0;
+    let mut iter = std::env::args().skip(1).peekable();
+    match iter.peek().map(String::as_str) {
+        Some("verify") => args.command = Command::Verify,
+        Some("bench") => args.command = Command::Bench,
+        Some("gen") => args.command = Command::Gen,
+        _ => {}
+    }
+    if !matches!(args.command, Command::Solve) {
+        iter.next();
+    }
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(eyre!("{arg} needs a value"));
+        match (&args.command, arg.as_str()) {
+            (Command::Solve | Command::Bench | Command::Gen, "-d" | "--day") => {
+                args.day = Some(value()?.parse()?)
+            }
+            (Command::Solve | Command::Bench, "-p" | "--part") => args.part = value()?.parse()?,
+            (Command::Solve | Command::Bench, "-i" | "--input") => args.input = Some(value()?),
+            (Command::Verify, "-a" | "--answers") => args.answers = Some(value()?.into()),
+            (Command::Bench, "-r" | "--runs") => match value()?.parse()? {
+                0 => return Err(eyre!("--runs needs to be at least 1")),
+                runs => args.runs = runs,
+            },
+            (Command::Bench, "--json") => args.json = true,
+            (Command::Gen, "-s" | "--size") => args.size = value()?.parse()?,
+            (Command::Gen, "--seed") => args.seed = Some(value()?.parse()?),
+            (Command::Gen, "--answers") => args.gen_answers = true,
+            (_, "-q" | "--quiet") => quiet = true,
+            (_, "-v" | "--verbose") => verbose += 1,
+            (_, "-vv") => verbose += 2,
+            (_, "-h" | "--help") => return Ok(None),
+            (_, unkn) => return Err(eyre!("Unknown argument {unkn}\n\n{USAGE}")),
+        }
+    }
+    if matches!(args.command, Command::Gen) && args.day.is_none() {
+        return Err(eyre!("gen needs a --day to generate the input for"));
+    }
+    if args.day.is_none() && args.input.is_some() {
+        return Err(eyre!(
+            "--input can only be used when solving a single --day"
+        ));
+    }
+    log::set_level(log::level_from_flags(quiet, verbose));
+    Ok(Some(args))
+}
+
+fn format_time(time: Duration) -> String {
+    format!("{time:.2?}")
+}
+
+fn format_part(part: &Option<runner::PartRun>) -> [String; 2] {
+    match part {
+        None => ["-".to_owned(), String::new()],
+        Some(p) => match &p.answer {
+            Ok(answer) => [answer.clone(), format_time(p.time)],
+            Err(e) => [format!("error: {e}"), format_time(p.time)],
+        },
+    }
+}
+
+fn summary_row(day: u8, run: &Result<Run>) -> Vec<String> {
+    match run {
+        Ok(run) => {
+            let [part1, part1_time] = format_part(&run.part1);
+            let [part2, part2_time] = format_part(&run.part2);
+            vec![
+                day.to_string(),
+                part1,
+                part2,
+                format_time(run.parse_time),
+                part1_time,
+                part2_time,
+                format_time(run.total_time()),
+            ]
+        }
+        Err(e) => vec![day.to_string(), format!("error: {e}")],
+    }
+}
+
+/// Prints a table with a column for each header, where cells can span several lines.
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (cell, width) in row.iter().zip(widths.iter_mut()) {
+            let cell_width = cell.lines().map(str::len).max().unwrap_or(0);
+            *width = (*width).max(cell_width);
+        }
+    }
+
+    let print_line = |cells: &[&str]| {
+        let line: Vec<_> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+
+    print_line(headers);
+    let rule: Vec<_> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    print_line(&rule.iter().map(String::as_str).collect::<Vec<_>>());
+    for row in rows {
+        let height = row.iter().map(|c| c.lines().count()).max().unwrap_or(1);
+        for line in 0..height.max(1) {
+            let cells: Vec<_> = row
+                .iter()
+                .map(|c| c.lines().nth(line).unwrap_or(""))
+                .collect();
+            print_line(&cells);
+        }
+    }
+}
+
+fn verify(answers: Option<PathBuf>) -> Result<()> {
+    let path = answers.unwrap_or_else(|| input::input_dir().join("answers.txt"));
+    let checks = verify::verify(&verify::load_answers(&path)?);
+
+    let count = |f: fn(&Outcome) -> bool| checks.iter().filter(|c| f(&c.outcome)).count();
+    for check in checks
+        .iter()
+        .filter(|c| log::enabled(Level::Normal) || c.outcome != Outcome::Pass)
+    {
+        println!("{check}");
+    }
+    let failures = count(|o| matches!(o, Outcome::Fail { .. } | Outcome::Error(_)));
+    if log::enabled(Level::Normal) {
+        println!(
+            "\n{} passed, {failures} failed, {} missing",
+            count(|o| *o == Outcome::Pass),
+            count(|o| *o == Outcome::Missing)
+        );
+    }
+
+    if failures > 0 {
+        return Err(eyre!("{failures} answer(s) didn't match"));
+    }
+    Ok(())
+}
+
+fn selected_days(args: &Args) -> Result<Vec<&'static Day>> {
+    match args.day {
+        Some(day) => Ok(vec![runner::find(day)?]),
+        None => Ok(runner::DAYS.iter().collect()),
+    }
+}
+
+fn stats_row(day: u8, step: &str, stats: &Option<Stats>) -> Vec<String> {
+    match stats {
+        Some(s) => vec![
+            day.to_string(),
+            step.to_owned(),
+            format_time(s.mean),
+            format_time(s.min),
+            format_time(s.stddev),
+        ],
+        None => vec![day.to_string(), step.to_owned(), "-".to_owned()],
+    }
+}
+
+fn bench(args: Args) -> Result<()> {
+    let mut results = Vec::new();
+    for day in selected_days(&args)? {
+        let source = input::source(day.day, args.input.as_deref());
+        let result = source
+            .read()
+            .and_then(|s| bench::bench(day, &s, args.part, args.runs))
+            .map_err(|e| parse::name_input(e, &source.to_string()));
+        results.push((day.day, result));
+    }
+
+    if args.json {
+        let days: Vec<_> = results
+            .iter()
+            .map(|(day, result)| match result {
+                Ok(bench) => bench.to_json(),
+                Err(e) => Json::object()
+                    .with("day", *day)
+                    .with("error", e.to_string()),
+            })
+            .collect();
+        println!(
+            "{}",
+            Json::object().with("runs", args.runs).with("days", days)
+        );
+    } else {
+        let mut rows = Vec::new();
+        for (day, result) in &results {
+            match result {
+                Ok(bench) => {
+                    rows.push(stats_row(*day, "parse", &Some(bench.parse)));
+                    rows.push(stats_row(*day, "part 1", &bench.part1));
+                    rows.push(stats_row(*day, "part 2", &bench.part2));
+                }
+                Err(e) => rows.push(vec![day.to_string(), format!("error: {e}")]),
+            }
+        }
+        print_table(&["Day", "Step", "Mean", "Min", "Std dev"], &rows);
+    }
+
+    let failures = results.iter().filter(|(_, r)| r.is_err()).count();
+    if failures > 0 {
+        return Err(eyre!("{failures} day(s) could not be benchmarked"));
+    }
+    Ok(())
+}
+
+fn solve(args: Args) -> Result<()> {
+    let days = selected_days(&args)?;
+
+    let mut rows = Vec::new();
+    let mut failures = 0;
+    for day in days {
+        let source = input::source(day.day, args.input.as_deref());
+        let run = source
+            .read()
+            .and_then(|s| (day.run)(&s, args.part))
+            .map_err(|e| parse::name_input(e, &source.to_string()));
+        let failed = match &run {
+            Ok(run) => [&run.part1, &run.part2]
+                .iter()
+                .any(|p| p.as_ref().is_some_and(|p| p.answer.is_err())),
+            Err(_) => true,
+        };
+        if failed {
+            failures += 1;
+        }
+        rows.push(summary_row(day.day, &run));
+    }
+
+    let headers = [
+        "Day", "Part 1", "Part 2", "Parse", "Time 1", "Time 2", "Total",
+    ];
+    if log::enabled(Level::Normal) {
+        print_table(&headers, &rows);
+    } else {
+        for row in &mut rows {
+            row.truncate(3);
+        }
+        print_table(&headers[..3], &rows);
+    }
+
+    if failures > 0 {
+        return Err(eyre!("{failures} day(s) could not be solved"));
+    }
+    Ok(())
+}
+
+fn generate(args: Args) -> Result<()> {
+    let day = runner::find(args.day.ok_or(eyre!("Missing --day"))?)?;
+    let seed = args.seed.unwrap_or_else(|| {
+        let seed = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |t| t.as_nanos() as u64);
+        if log::enabled(Level::Normal) {
+            eprintln!("Generating with --seed {seed}");
+        }
+        seed
+    });
+
+    let input = (day.generate)(&mut Rng::new(seed), args.size);
+    print!("{input}");
+
+    if args.gen_answers {
+        for (part, answer) in (1..).zip((day.reference)(&input)) {
+            let Some(answer) = answer else { continue };
+            let expected = Expected {
+                day: day.day,
+                part,
+                input: format!("day{}.txt", day.day).into(),
+                answer,
+            };
+            eprintln!("{expected}");
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let Some(args) = parse_args()? else {
+        println!("{USAGE}");
+        return Ok(());
+    };
+
+    match args.command {
+        Command::Solve => solve(args),
+        Command::Verify => verify(args.answers),
+        Command::Bench => bench(args),
+        Command::Gen => generate(args),
+    }
+}
@@ -1,71 +1,183 @@
-use std::cmp::Ordering;
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-#[derive(Debug, Default, PartialEq, Eq)]
-struct Elf {
-    // Calorie counts of this elf's inventory
-    item_calories: Vec<usize>,
-    total_calories: usize,
-}
+use advent2022::balance::{Goal, Plan};
+use advent2022::cli::{self, DayOption, Format};
+use advent2022::day1::{self, CalorieStats, Day1, Elf, InventoryFormat};
+use advent2022::input::{self, Source};
+use advent2022::json::Json;
+use advent2022::{debug, log, parse, Solution};
+use color_eyre::{eyre::eyre, Result};
 
-impl PartialOrd for Elf {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+const SHOW: DayOption = DayOption {
+    name: "--show",
+    value: Some("N"),
+    help: "List the top N elves with -v (default: 5)",
+};
+
+const SUM: DayOption = DayOption {
+    name: "--sum",
+    value: Some("N"),
+    help: "Add up what the top N elves carry for part two (default: 3)",
+};
+
+const STATS: DayOption = DayOption {
+    name: "--stats",
+    value: None,
+    help: "Print statistics about what every elf carries instead",
+};
+
+const BUCKETS: DayOption = DayOption {
+    name: "--buckets",
+    value: Some("N"),
+    help: "Split the statistics' histogram into N buckets (default: 10)",
+};
+
+const COMMENT: DayOption = DayOption {
+    name: "--comment",
+    value: Some("PREFIX"),
+    help: "Ignore lines starting with PREFIX (default: #)",
+};
+
+const SEPARATOR: DayOption = DayOption {
+    name: "--separator",
+    value: Some("LINE"),
+    help: "Separate elves with LINE as well as blank lines",
+};
+
+const REBALANCE: DayOption = DayOption {
+    name: "--rebalance",
+    value: Some("MOVES"),
+    help: "Plan up to MOVES item moves to even out the elves instead",
+};
+
+const GOAL: DayOption = DayOption {
+    name: "--goal",
+    value: Some("GOAL"),
+    help: "Have --rebalance bring down the max (default) or the spread",
+};
+
+/// Reads every elf's inventory at once.
+fn read_elves(source: &Source, format: &InventoryFormat) -> Result<Vec<Elf>> {
+    day1::parse_inventory(&source.read()?, format)
+        .map_err(|e| parse::name_input(e.into(), &source.to_string()))
 }
 
-impl Ord for Elf {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.total_calories.cmp(&other.total_calories)
+/// Who an elf is, by name if they have one.
+fn elf_name(elves: &[Elf], idx: usize) -> String {
+    match &elves[idx].name {
+        Some(name) => name.clone(),
+        None => format!("elf {}", idx + 1),
     }
 }
 
-impl Elf {
-    fn add_item(&mut self, calories: usize) {
-        self.item_calories.push(calories);
-        self.total_calories += calories;
+fn print_plan(elves: &[Elf], goal: Goal, plan: &Plan, format: Format) {
+    let (before, after) = match goal {
+        Goal::Max => plan.max(),
+        Goal::Spread => plan.spread(),
+    };
+    if format == Format::Json {
+        let moves: Vec<_> = plan
+            .moves
+            .iter()
+            .map(|m| {
+                Json::object()
+                    .with("item", m.item)
+                    .with("from", m.from + 1)
+                    .with("to", m.to + 1)
+            })
+            .collect();
+        let goal = match goal {
+            Goal::Max => "max",
+            Goal::Spread => "spread",
+        };
+        let json = Json::object()
+            .with("goal", goal)
+            .with("before", before)
+            .with("after", after)
+            .with("moves", moves)
+            .with("totals", plan.after.clone());
+        println!("{json}");
+        return;
+    }
+    for m in &plan.moves {
+        log::answer(
+            format_args!("{} {} {}", m.item, m.from + 1, m.to + 1),
+            format_args!(
+                "Move the {} calorie item from {} to {}",
+                m.item,
+                elf_name(elves, m.from),
+                elf_name(elves, m.to)
+            ),
+        );
     }
+    let measure = match goal {
+        Goal::Max => "The most any elf carries",
+        Goal::Spread => "The spread between elves",
+    };
+    let moves = plan.moves.len();
+    log::answer(
+        after,
+        format_args!("{measure} goes from {before} to {after} calories in {moves} moves"),
+    );
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let mut elves = Vec::new();
+fn main() -> Result<()> {
+    let options = [
+        SHOW, SUM, STATS, BUCKETS, COMMENT, SEPARATOR, REBALANCE, GOAL,
+    ];
+    let Some(args) = cli::day_args_with(Day1::DAY, &options)? else {
+        return Ok(());
+    };
+    let format = InventoryFormat {
+        comment: args.value(COMMENT.name, "#".to_owned())?,
+        separator: args.get(SEPARATOR.name)?,
+    };
+    let source = input::source(Day1::DAY, args.input.as_deref());
 
-    let file = BufReader::new(File::open("day1.txt")?);
-
-    let mut new_elf = Elf::default();
-    for line in file.lines() {
-        let line = line?;
-        if line.is_empty() {
-            let done_elf = std::mem::replace(&mut new_elf, Elf::default());
-            elves.push(done_elf);
-        } else {
-            new_elf.add_item(line.parse()?);
+    if let Some(max_moves) = args.get(REBALANCE.name)? {
+        let elves = read_elves(&source, &format)?;
+        let goal = args.value(GOAL.name, Goal::Max)?;
+        print_plan(
+            &elves,
+            goal,
+            &day1::rebalance(&elves, goal, max_moves),
+            args.format,
+        );
+        return Ok(());
+    }
+    if args.flag(STATS.name) {
+        let elves = read_elves(&source, &format)?;
+        let stats = CalorieStats::new(&elves, args.value(BUCKETS.name, 10)?)
+            .ok_or(eyre!("There are no elves"))?;
+        match args.format {
+            Format::Text => println!("{stats}"),
+            Format::Json => println!("{}", Json::from(&stats)),
         }
+        return Ok(());
     }
-    // Add the last elf if for some reason we didn't end with an empty line
-    if !new_elf.item_calories.is_empty() {
-        elves.push(new_elf);
+    if args.format == Format::Json {
+        args.print_json::<Day1>(&read_elves(&source, &format)?);
+        return Ok(());
     }
+    let show = args.value(SHOW.name, 5)?;
+    let sum = args.value(SUM.name, 3)?;
 
-    elves.as_mut_slice().sort();
-    elves.as_mut_slice().reverse();
-    let _rest = elves.split_off(5);
+    let top = day1::read_top_elves(source.reader()?, &format, show.max(sum).max(1))
+        .map_err(|e| parse::name_input(e, &source.to_string()))?;
 
-    let mut total_for_top_3 = 0;
-    for (i, elf) in elves.iter().enumerate() {
-        println!(
+    for (i, elf) in top.ranked().iter().take(show).enumerate() {
+        debug!(
             "Elf #{i}: Carrying {} items for {} calories",
             elf.item_calories.len(),
             elf.total_calories
         );
-        if i < 3 {
-            total_for_top_3 += elf.total_calories;
-        }
     }
 
-    println!("Top three elves are carrying {total_for_top_3} calories");
+    let most = top.total(1);
+    log::answer(most, format_args!("Top elf is carrying {most} calories"));
+    let total_for_top = top.total(sum);
+    log::answer(
+        total_for_top,
+        format_args!("Top {sum} elves are carrying {total_for_top} calories"),
+    );
 
     Ok(())
 }
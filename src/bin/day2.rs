@@ -1,121 +1,125 @@
-use color_eyre::{eyre::eyre, Report, Result};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::str::FromStr;
+use advent2022::cli::{self, DayOption, Format};
+use advent2022::day2::analysis;
+use advent2022::day2::rules::Game;
+use advent2022::day2::tournament::{self, DEFAULT_PLAYERS};
+use advent2022::day2::{Day2, Reading};
+use advent2022::input::{self, Source};
+use advent2022::json::Json;
+use advent2022::{log, parse, Solution};
+use color_eyre::eyre::WrapErr;
+use color_eyre::Result;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum Shape {
-    Scissors,
-    Paper,
-    Rock,
-}
+const RULES: DayOption = DayOption {
+    name: "--rules",
+    value: Some("FILE"),
+    help: "Play the game defined in FILE, like inputs/games/rpsls.txt",
+};
 
-impl FromStr for Shape {
-    type Err = Report;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 1 {
-            return Err(eyre!("Shapes are only one letter, not {}", s.len()));
-        }
-        match s.chars().next().unwrap() {
-            'A' | 'X' => Ok(Shape::Rock),
-            'B' | 'Y' => Ok(Shape::Paper),
-            'C' | 'Z' => Ok(Shape::Scissors),
-            _ => Err(eyre!("Invalid shape string")),
-        }
-    }
-}
+const TOURNAMENT: DayOption = DayOption {
+    name: "--tournament",
+    value: Some("ROUNDS"),
+    help: "Play a round-robin tournament of ROUNDS-round matches instead",
+};
 
-#[derive(Debug, PartialEq)]
-enum GameResult {
-    Win,
-    Draw,
-    Loss,
-}
+const PLAYERS: DayOption = DayOption {
+    name: "--players",
+    value: Some("LIST"),
+    help: "Who plays in the tournament, like rock,cycle:rps,random:7,counter,guide",
+};
 
-impl GameResult {
-    fn score(&self) -> u64 {
-        match self {
-            GameResult::Win => 6,
-            GameResult::Draw => 3,
-            GameResult::Loss => 0,
-        }
-    }
-}
+const ANALYSIS: DayOption = DayOption {
+    name: "--analysis",
+    value: None,
+    help: "Show the best, worst and average scores against the guide's opponent instead",
+};
 
-impl FromStr for GameResult {
-    type Err = Report;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 1 {
-            return Err(eyre!("Results are only one letter, not {}", s.len()));
-        }
-        match s.chars().next().unwrap() {
-            'X' => Ok(GameResult::Loss),
-            'Y' => Ok(GameResult::Draw),
-            'Z' => Ok(GameResult::Win),
-            _ => Err(eyre!("Invalid result string")),
-        }
-    }
-}
+/// Scores the guide for a game read from `rules`, by shape names or letters.
+fn play_rules(rules: &str, args: &cli::DayArgs) -> Result<()> {
+    let rules = Source::from_arg(rules);
+    let game: Game = rules
+        .read()?
+        .parse()
+        .map_err(|e: parse::ParseErrors| parse::name_input(e.into(), &rules.to_string()))?;
+    let source = input::source(Day2::DAY, args.input.as_deref());
+    let guide = source.read()?;
+    let [as_shapes, as_results] = Reading::BOTH.map(|reading| {
+        game.score_guide(&guide, reading)
+            .map_err(|e| parse::name_input(e.into(), &source.to_string()))
+    });
 
-impl Shape {
-    /// Returns the shape you throw to get the result given when playing against this shape.
-    fn for_result(&self, result: GameResult) -> Self {
-        use GameResult::*;
-        use Shape::*;
-        match (self, result) {
-            (x, Draw) => *x,
-            (Scissors, Win) => Rock,
-            (Rock, Win) => Paper,
-            (Paper, Win) => Scissors,
-            (Scissors, Loss) => Paper,
-            (Rock, Loss) => Scissors,
-            (Paper, Loss) => Rock,
-        }
+    if args.format == Format::Json {
+        let shapes: Vec<_> = game.shapes().map(|s| game.name(s)).collect();
+        let json = Json::object()
+            .with("shapes", shapes)
+            .with("part1", as_shapes)
+            .with("part2", as_results);
+        println!("{json}");
+        return Ok(());
     }
-
-    fn value(&self) -> u64 {
-        match self {
-            Shape::Scissors => 3,
-            Shape::Paper => 2,
-            Shape::Rock => 1,
-        }
+    // The second column may only make sense one way, so give whichever answer there is before
+    // failing over the other.
+    if let Ok(score) = &as_shapes {
+        log::answer(score, format_args!("Total score reading shapes: {score}"));
     }
-
-    fn versus(&self, opp: &Self) -> GameResult {
-        use GameResult::*;
-        use Shape::*;
-        match (self, opp) {
-            (Scissors, Paper) => Win,
-            (Paper, Rock) => Win,
-            (Rock, Scissors) => Win,
-            (x, y) if x == y => Draw,
-            _ => Loss,
-        }
+    if let Ok(score) = &as_results {
+        log::answer(
+            score,
+            format_args!("Total score reading X, Y and Z as results: {score}"),
+        );
     }
+    as_shapes.wrap_err("Can't read the guide as shapes")?;
+    as_results.wrap_err("Can't read the guide as results")?;
+    Ok(())
+}
 
-    /// Returns the score you receive if you play against opp
-    fn score(&self, opp: &Self) -> u64 {
-        self.value() + self.versus(opp).score()
+fn play_tournament(rounds: usize, args: &cli::DayArgs) -> Result<()> {
+    let guide = input::parse::<Day2>(args.input.as_deref())?;
+    let players = args.value(PLAYERS.name, DEFAULT_PLAYERS.to_owned())?;
+    let mut players = players
+        .split(',')
+        .map(|spec| tournament::strategy(spec, &guide))
+        .collect::<Result<Vec<_>>>()?;
+    let tournament = tournament::round_robin(&mut players, rounds);
+    match args.format {
+        Format::Text => println!("{tournament}"),
+        Format::Json => println!("{}", Json::from(&tournament)),
     }
+    Ok(())
 }
 
 fn main() -> Result<()> {
-    let file = BufReader::new(File::open("day2.txt")?);
-
-    let mut total_score = 0;
-    for line in file.lines() {
-        let line = line?;
-        let mut game: Vec<_> = line.split(' ').collect();
-        let my_result = game.pop().unwrap().parse::<GameResult>()?;
-        let their_throw = game.pop().unwrap().parse::<Shape>()?;
-        let my_throw = their_throw.for_result(my_result);
-
-        let score = my_throw.score(&their_throw);
-        println!("Played {my_throw:?} vs {their_throw:?} and got {score} points");
-        total_score += score;
+    let options = [RULES, TOURNAMENT, PLAYERS, ANALYSIS];
+    let Some(args) = cli::day_args_with(Day2::DAY, &options)? else {
+        return Ok(());
+    };
+    if let Some(rules) = args.get::<String>(RULES.name)? {
+        return play_rules(&rules, &args);
+    }
+    if let Some(rounds) = args.get(TOURNAMENT.name)? {
+        return play_tournament(rounds, &args);
+    }
+    let guide = input::parse::<Day2>(args.input.as_deref())?;
+    if args.flag(ANALYSIS.name) {
+        let analysis = analysis::analyse(&guide);
+        match args.format {
+            Format::Text => println!("{analysis}"),
+            Format::Json => println!("{}", Json::from(&analysis)),
+        }
+        return Ok(());
+    }
+    if args.print_json::<Day2>(&guide) {
+        return Ok(());
     }
 
-    println!("Total score: {total_score}");
+    let [as_shapes, as_results] = guide.scores();
+    log::answer(
+        as_shapes,
+        format_args!("Total score reading X, Y and Z as shapes: {as_shapes}"),
+    );
+    log::answer(
+        as_results,
+        format_args!("Total score reading X, Y and Z as results: {as_results}"),
+    );
 
     Ok(())
 }
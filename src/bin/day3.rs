@@ -1,47 +1,110 @@
+use advent2022::cli::{self, DayOption, Format};
+use advent2022::day3::{self, repack, Day3, Grouping, Windows};
+use advent2022::json::Json;
+use advent2022::{debug, input, log, Solution};
 use color_eyre::Result;
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-fn priority(c: char) -> u32 {
-    if c.is_uppercase() {
-        c as u32 - 'A' as u32 + 27
-    } else {
-        c as u32 - 'a' as u32 + 1
-    }
-}
-
-fn main() -> Result<()> {
-    let file = BufReader::new(File::open("day3.txt")?);
-
-    let mut sum = 0;
 
-    let mut iter = file.lines();
+const GROUPS: DayOption = DayOption {
+    name: "--groups",
+    value: None,
+    help: "Report the groups that don't share exactly one badge instead",
+};
 
-    loop {
-        let first = match iter.next() {
-            Some(first) => first?,
-            None => break,
-        };
-        let second = iter.next().unwrap()?;
-        let third = iter.next().unwrap()?;
+const GROUP_SIZE: DayOption = DayOption {
+    name: "--group-size",
+    value: Some("N"),
+    help: "Group N rucksacks together for --groups (default: 3)",
+};
 
-        let first_set: HashSet<char> = first.chars().collect();
-        let second_set: HashSet<char> = second.chars().collect();
-        let third_set: HashSet<char> = third.chars().collect();
+const SLIDING: DayOption = DayOption {
+    name: "--sliding",
+    value: None,
+    help: "Make every N rucksacks in a row a group for --groups",
+};
 
-        let intersection: char = first_set
-            .intersection(&second_set)
-            .find(|c| third_set.contains(c))
-            .unwrap()
-            .clone();
+const REPACK: DayOption = DayOption {
+    name: "--repack",
+    value: None,
+    help: "Plan how to repack so no item is in both compartments instead",
+};
 
-        let priority = priority(intersection);
-        sum += priority;
+fn main() -> Result<()> {
+    let options = [GROUPS, GROUP_SIZE, SLIDING, REPACK];
+    let Some(args) = cli::day_args_with(Day3::DAY, &options)? else {
+        return Ok(());
+    };
+    let rucksacks = input::parse::<Day3>(args.input.as_deref())?;
 
-        println!("{first} and {second} and {third} share {intersection:?} (pri {priority})");
+    if args.flag(REPACK.name) {
+        let plans: Vec<_> = rucksacks.iter().map(repack::repack).collect();
+        if args.format == Format::Json {
+            let plans: Vec<_> = plans.iter().map(Json::from).collect();
+            println!("{}", Json::object().with("rucksacks", plans));
+            return Ok(());
+        }
+        for (idx, plan) in plans.iter().enumerate() {
+            for item_move in &plan.moves {
+                debug!("Rucksack {}: {item_move}", idx + 1);
+            }
+        }
+        let moved: usize = plans.iter().map(|p| p.items_moved()).sum();
+        let saved: u32 = plans.iter().map(|p| p.saved).sum();
+        log::answer(
+            moved,
+            format_args!("Moving {moved} items leaves no item in both compartments"),
+        );
+        log::answer(
+            saved,
+            format_args!("That saves items with priorities adding up to {saved}"),
+        );
+        return Ok(());
+    }
+    if args.flag(GROUPS.name) {
+        let grouping = Grouping {
+            size: args.value(GROUP_SIZE.name, 3)?,
+            windows: match args.flag(SLIDING.name) {
+                true => Windows::Sliding,
+                false => Windows::Fixed,
+            },
+        };
+        let groups = day3::groups(&rucksacks, grouping)?;
+        if args.format == Format::Json {
+            let groups: Vec<_> = groups.iter().map(Json::from).collect();
+            println!("{}", Json::object().with("groups", groups));
+            return Ok(());
+        }
+        for group in &groups {
+            match group.badge() {
+                Some(_) => debug!("{group}"),
+                None => log::answer(group, group),
+            }
+        }
+        let badges: Vec<_> = groups.iter().filter_map(|g| g.badge()).collect();
+        let sum: u32 = badges.iter().filter_map(|&b| day3::priority(b)).sum();
+        log::answer(
+            sum,
+            format_args!(
+                "{} of {} groups have one badge, with priorities adding up to {sum}",
+                badges.len(),
+                groups.len()
+            ),
+        );
+        return Ok(());
     }
-    println!("sum: {sum}");
+    if args.print_json::<Day3>(&rucksacks) {
+        return Ok(());
+    }
+
+    let misplaced = Day3::part1(&rucksacks)?;
+    log::answer(
+        misplaced,
+        format_args!("Items in both compartments have priorities adding up to {misplaced}"),
+    );
+    let badges = Day3::part2(&rucksacks)?;
+    log::answer(
+        badges,
+        format_args!("Badges have priorities adding up to {badges}"),
+    );
 
     Ok(())
 }
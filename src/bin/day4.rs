@@ -1,63 +1,121 @@
-use color_eyre::{eyre::eyre, Report, Result};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::str::FromStr;
-
-#[derive(Debug)]
-struct CleaningRange {
-    first: u64,
-    last: u64,
-}
+use advent2022::cli::{self, DayOption, Format};
+use advent2022::day4::roster::{Elf, Roster};
+use advent2022::day4::{self, coverage, CleaningRange, Day4, Reversed};
+use advent2022::json::Json;
+use advent2022::{info, input, log, parse, Solution};
+use color_eyre::Result;
+
+const COVERAGE: DayOption = DayOption {
+    name: "--coverage",
+    value: None,
+    help: "Report which sections the whole roster covers instead",
+};
+
+const CROWD: DayOption = DayOption {
+    name: "--crowd",
+    value: Some("K"),
+    help: "Count sections covered by more than K elves for --coverage (default: 1)",
+};
+
+const COVERING: DayOption = DayOption {
+    name: "--covering",
+    value: Some("SECTION"),
+    help: "List the elves assigned SECTION instead",
+};
+
+const OVERLAPPING: DayOption = DayOption {
+    name: "--overlapping",
+    value: Some("RANGE"),
+    help: "List the elves assigned any section in RANGE, such as 3-7, instead",
+};
+
+const PAIRS: DayOption = DayOption {
+    name: "--pairs",
+    value: None,
+    help: "List every two elves anywhere in the input who share a section instead",
+};
 
-impl CleaningRange {
-    fn contains(&self, other: &Self) -> bool {
-        self.first <= other.first && self.last >= other.last
+const NORMALISE: DayOption = DayOption {
+    name: "--normalise",
+    value: None,
+    help: "Turn round ranges written backwards, like 7-3, rather than stopping at them",
+};
+
+fn main() -> Result<()> {
+    let options = [NORMALISE, COVERAGE, CROWD, COVERING, OVERLAPPING, PAIRS];
+    let Some(args) = cli::day_args_with(Day4::DAY, &options)? else {
+        return Ok(());
+    };
+    let source = input::source(Day4::DAY, args.input.as_deref());
+    let reversed = match args.flag(NORMALISE.name) {
+        true => Reversed::Swap,
+        false => Reversed::Reject,
+    };
+    let (pairs, swaps) = day4::parse_pairs_with(&source.read()?, reversed)
+        .map_err(|e| parse::name_input(e.into(), &source.to_string()))?;
+    for swap in &swaps {
+        info!("{swap}");
     }
 
-    fn overlaps(&self, other: &Self) -> bool {
-        (other.last >= self.last && other.first <= self.last)
-            || (other.first <= self.first && other.last >= self.first)
+    if args.flag(COVERAGE.name) {
+        let coverage = coverage::coverage(&day4::assignments(&pairs), args.value(CROWD.name, 1)?);
+        match args.format {
+            Format::Text => println!("{coverage}"),
+            Format::Json => println!("{}", Json::from(&coverage)),
+        }
+        return Ok(());
+    }
+    let section: Option<u64> = args.get(COVERING.name)?;
+    let range: Option<CleaningRange> = args.get(OVERLAPPING.name)?;
+    if section.is_some() || range.is_some() || args.flag(PAIRS.name) {
+        let roster = Roster::new(&day4::assignments(&pairs));
+        if let Some(section) = section {
+            print_elves(args.format, &roster.covering(section));
+        } else if let Some(range) = range {
+            print_elves(args.format, &roster.overlapping(&range));
+        } else {
+            print_pairs(args.format, &roster.overlapping_pairs());
+        }
+        return Ok(());
     }
+    if args.print_json::<Day4>(&pairs) {
+        return Ok(());
+    }
+
+    let completely_overlaps = Day4::part1(&pairs)?;
+    let partially_overlaps = Day4::part2(&pairs)?;
+
+    log::answer(
+        completely_overlaps,
+        format_args!("Completely overlaps: {completely_overlaps}"),
+    );
+    log::answer(
+        partially_overlaps,
+        format_args!("Partially overlaps: {partially_overlaps}"),
+    );
+
+    Ok(())
 }
 
-impl FromStr for CleaningRange {
-    type Err = Report;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (first, last) = s
-            .split_once('-')
-            .ok_or(eyre!("Missing a '-' in this {s}"))?;
-        Ok(Self {
-            first: first.parse()?,
-            last: last.parse()?,
-        })
+fn print_elves(format: Format, elves: &[Elf]) {
+    match format {
+        Format::Text => elves.iter().for_each(|elf| println!("{elf}")),
+        Format::Json => {
+            let elves: Vec<_> = elves.iter().map(Json::from).collect();
+            println!("{}", Json::object().with("elves", elves));
+        }
     }
 }
 
-fn main() -> Result<()> {
-    let file = BufReader::new(File::open("day4.txt")?);
-
-    let mut completely_overlaps = 0;
-    let mut partially_overlaps = 0;
-
-    let mut iter = file.lines();
-
-    while let Some(line) = iter.next() {
-        let line = line?;
-        let (first, second) = line.split_once(',').unwrap();
-        let f_range = CleaningRange::from_str(first)?;
-        let s_range = CleaningRange::from_str(second)?;
-        if f_range.contains(&s_range) || s_range.contains(&f_range) {
-            println!("{f_range:?} completely overlaps {s_range:?}");
-            completely_overlaps += 1;
-            partially_overlaps += 1;
-        } else if f_range.overlaps(&s_range) || s_range.overlaps(&f_range) {
-            println!("{f_range:?} partially overlaps {s_range:?}");
-            partially_overlaps += 1;
+fn print_pairs(format: Format, pairs: &[(Elf, Elf)]) {
+    match format {
+        Format::Text => pairs.iter().for_each(|(a, b)| println!("{a} and {b}")),
+        Format::Json => {
+            let pairs: Vec<_> = pairs
+                .iter()
+                .map(|(a, b)| Json::from(vec![Json::from(a), Json::from(b)]))
+                .collect();
+            println!("{}", Json::object().with("pairs", pairs));
         }
     }
-
-    println!("Completely overlaps: {completely_overlaps}");
-    println!("Partially overlaps: {partially_overlaps}");
-
-    Ok(())
 }
@@ -1,21 +1,22 @@
-use color_eyre::Result;
-use std::collections::HashSet;
-
-const UNIQUE_CHARS: usize = 14;
+use advent2022::cli::{self, Format};
+use advent2022::day6::{self, Day6, MESSAGE_MARKER_LEN};
+use advent2022::{input, log, Solution};
+use color_eyre::{eyre::eyre, Result};
 
 fn main() -> Result<()> {
-    let mut iter = include_str!("../../day6.txt").lines();
-
-    // Get the crates into the stacks
-    while let Some(line) = iter.next() {
-        let mut end_idx = UNIQUE_CHARS;
-        let mut startofmessage: &str = line.get((end_idx - UNIQUE_CHARS)..end_idx).unwrap();
-        while startofmessage.chars().collect::<HashSet<_>>().len() != UNIQUE_CHARS {
-            end_idx += 1;
-            startofmessage = line.get((end_idx - UNIQUE_CHARS)..end_idx).unwrap();
-        }
+    let Some(args) = cli::day_args(Day6::DAY)? else {
+        return Ok(());
+    };
+    let stream = input::load(Day6::DAY, args.input.as_deref())?;
+    if args.format == Format::Json {
+        args.print_json::<Day6>(&Day6::parse(&stream)?);
+        return Ok(());
+    }
+    for line in stream.lines() {
+        let end_idx =
+            day6::marker_end(line, MESSAGE_MARKER_LEN).ok_or(eyre!("No marker in {line}"))?;
 
-        println!("Start at {end_idx}");
+        log::answer(end_idx, format_args!("Start at {end_idx}"));
     }
 
     Ok(())
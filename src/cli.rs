@@ -0,0 +1,212 @@
+//! Command line options shared by the `dayN` binaries.
+
+use color_eyre::{eyre::eyre, Report, Result};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::json;
+use crate::{log, Solution};
+
+/// How a `dayN` binary writes its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The answers, explained in words.
+    Text,
+    /// The answers and the day's details as one JSON document, see [`json::report`].
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Report;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(eyre!("Format must be text or json, not {s}")),
+        }
+    }
+}
+
+/// An option only some days take, as `--name VALUE`, or just `--name` for a flag.
+pub struct DayOption {
+    pub name: &'static str,
+    /// What the value is called in the usage, or `None` for a flag.
+    pub value: Option<&'static str>,
+    pub help: &'static str,
+}
+
+/// Options every `dayN` binary accepts.
+pub struct DayArgs {
+    /// Where to read the input from, see [`crate::input::source`].
+    pub input: Option<String>,
+    pub format: Format,
+    /// The day's own options that were given, with their values (empty for flags).
+    pub options: Vec<(&'static str, String)>,
+}
+
+impl DayArgs {
+    /// Whether the day's option `name` was given.
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| *n == name)
+    }
+
+    /// The value of the day's option `name`, if it was given. The last one wins if it was given
+    /// more than once.
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<Option<T>>
+    where
+        T::Err: Display,
+    {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| {
+                value
+                    .parse()
+                    .map_err(|e| eyre!("Invalid {name} {value}: {e}"))
+            })
+            .transpose()
+    }
+
+    /// The value of the day's option `name`, or `default` if it wasn't given.
+    pub fn value<T: FromStr>(&self, name: &str, default: T) -> Result<T>
+    where
+        T::Err: Display,
+    {
+        Ok(self.get(name)?.unwrap_or(default))
+    }
+
+    /// Prints the JSON report for `input` if it was asked for, returning whether it was.
+    pub fn print_json<S: Solution>(&self, input: &S::Input) -> bool {
+        if self.format == Format::Json {
+            println!("{}", json::report::<S>(input));
+        }
+        self.format == Format::Json
+    }
+}
+
+fn usage(name: &str, day_options: &[DayOption]) -> String {
+    let mut options = String::new();
+    for option in day_options {
+        let flag = match option.value {
+            Some(value) => format!("{} {value}", option.name),
+            None => option.name.to_owned(),
+        };
+        // Long ones get their help on the next line, still lined up with the rest.
+        match flag.len() {
+            0..=10 => options += &format!("\n      {flag:<10} {}", option.help),
+            _ => options += &format!("\n      {flag}\n{:17}{}", "", option.help),
+        }
+    }
+    format!(
+        "Usage: {name} [OPTIONS] [INPUT]
+
+Reads INPUT, or stdin if INPUT is '-', or dayN.txt in $AOC_INPUT_DIR or the current directory
+otherwise, and prints the answers.
+
+Options:
+  -q, --quiet    Only print the answers
+  -v, --verbose  Explain how the answers were found, twice to show every step
+  -f, --format   Write the answers as text (the default), or as JSON with the
+                 intermediate results that led to them{options}
+  -h, --help     Show this help"
+    )
+}
+
+/// Parses the options in `args` (not including the program name), setting the log level.
+///
+/// Returns `None` if only the usage was asked for, after printing it.
+pub fn parse_day_args(
+    name: &str,
+    day_options: &[DayOption],
+    args: impl IntoIterator<Item = String>,
+) -> Result<Option<DayArgs>> {
+    let usage = || usage(name, day_options);
+    let mut input = None;
+    let mut options = Vec::new();
+    let mut format = Format::Text;
+    let mut quiet = false;
+    let mut verbose = 0;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-f" | "--format" => {
+                format = args
+                    .next()
+                    .ok_or(eyre!("{arg} needs a value\n\n{}", usage()))?
+                    .parse()?
+            }
+            "-q" | "--quiet" => quiet = true,
+            "-v" | "--verbose" => verbose += 1,
+            "-vv" => verbose += 2,
+            "-h" | "--help" => {
+                println!("{}", usage());
+                return Ok(None);
+            }
+            flag if flag.starts_with("--") && day_options.iter().any(|o| o.name == flag) => {
+                let option = day_options.iter().find(|o| o.name == flag).unwrap();
+                let value = match option.value {
+                    Some(_) => args
+                        .next()
+                        .ok_or(eyre!("{arg} needs a value\n\n{}", usage()))?,
+                    None => String::new(),
+                };
+                options.push((option.name, value));
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                return Err(eyre!("Unknown option {flag}\n\n{}", usage()))
+            }
+            path if input.is_none() => input = Some(path.to_owned()),
+            extra => return Err(eyre!("Unexpected argument {extra}\n\n{}", usage())),
+        }
+    }
+    log::set_level(log::level_from_flags(quiet, verbose));
+    Ok(Some(DayArgs {
+        input,
+        format,
+        options,
+    }))
+}
+
+/// Parses the options of the running `dayN` binary.
+pub fn day_args(day: u8) -> Result<Option<DayArgs>> {
+    day_args_with(day, &[])
+}
+
+/// Parses the options of the running `dayN` binary, which also takes `day_options`.
+pub fn day_args_with(day: u8, day_options: &[DayOption]) -> Result<Option<DayArgs>> {
+    parse_day_args(&format!("day{day}"), day_options, std::env::args().skip(1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SHOW: DayOption = DayOption {
+        name: "--show",
+        value: Some("N"),
+        help: "How many to show",
+    };
+
+    fn args(s: &str) -> Result<Option<DayArgs>> {
+        parse_day_args("day1", &[SHOW], s.split_whitespace().map(str::to_owned))
+    }
+
+    #[test]
+    fn parses_day_options() {
+        let parsed = args("--show 7 -f json day1.txt").unwrap().unwrap();
+        assert_eq!(Some("day1.txt"), parsed.input.as_deref());
+        assert_eq!(Format::Json, parsed.format);
+        assert_eq!(7, parsed.value("--show", 5).unwrap());
+        assert!(!parsed.flag("--sum"));
+        assert_eq!(3, parsed.value("--sum", 3).unwrap());
+
+        assert!(args("--show").is_err());
+        assert!(args("--sum 2").is_err());
+        assert!(args("--show x")
+            .unwrap()
+            .unwrap()
+            .value("--show", 5)
+            .is_err());
+    }
+}
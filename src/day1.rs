@@ -0,0 +1,631 @@
+use color_eyre::{eyre::WrapErr, Report, Result};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+use std::io::BufRead;
+
+use crate::balance::{self, Goal, Plan};
+use crate::gen::{Generator, Rng};
+use crate::json::Json;
+use crate::parse::{self, ParseError, ParseErrors};
+use crate::Solution;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Elf {
+    /// The elf's name, if the inventory gives one.
+    pub name: Option<String>,
+    /// Calorie counts of this elf's inventory
+    pub item_calories: Vec<usize>,
+    pub total_calories: usize,
+    /// Calories of the smallest and largest items, if there are any.
+    pub smallest_item: Option<usize>,
+    pub largest_item: Option<usize>,
+}
+
+impl PartialOrd for Elf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Elf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_calories.cmp(&other.total_calories)
+    }
+}
+
+impl Elf {
+    pub fn add_item(&mut self, calories: usize) {
+        self.item_calories.push(calories);
+        self.total_calories += calories;
+        self.smallest_item = Some(self.smallest_item.map_or(calories, |s| s.min(calories)));
+        self.largest_item = Some(self.largest_item.map_or(calories, |l| l.max(calories)));
+    }
+}
+
+/// How to read an inventory, which always has one item per line, with elves separated by blank
+/// lines.
+///
+/// Whitespace around lines (including the `\r` of CRLF line endings) is ignored, and a line
+/// ending in `:`, like `Alice:`, names the elf whose items follow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryFormat {
+    /// Lines starting with this are ignored.
+    pub comment: String,
+    /// A line that separates elves as well as blank ones, like `---`.
+    pub separator: Option<String>,
+}
+
+impl Default for InventoryFormat {
+    fn default() -> Self {
+        Self {
+            comment: "#".to_owned(),
+            separator: None,
+        }
+    }
+}
+
+/// What a line of an inventory says.
+enum InventoryLine<'a> {
+    Item(usize),
+    Separator,
+    Label(&'a str),
+    Comment,
+}
+
+impl InventoryFormat {
+    fn read_line<'a>(&self, line: &'a str) -> Result<InventoryLine<'a>, ParseError> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || self.separator.as_deref() == Some(trimmed) {
+            Ok(InventoryLine::Separator)
+        } else if !self.comment.is_empty() && trimmed.starts_with(&self.comment) {
+            Ok(InventoryLine::Comment)
+        } else if let Some(name) = trimmed.strip_suffix(':') {
+            Ok(InventoryLine::Label(name.trim()))
+        } else if trimmed.starts_with('-') && trimmed[1..].parse::<usize>().is_ok() {
+            Err(ParseError::at("Calories can't be negative", line, trimmed))
+        } else {
+            parse::number(trimmed, "Calories must be a whole number")
+                .map(InventoryLine::Item)
+                .map_err(|e| e.within(line, trimmed))
+        }
+    }
+}
+
+/// Reads the inventories, handing each elf to `f` as soon as their inventory ends, and carrying on
+/// past any lines that can't be read to report them all at the end.
+fn each_elf<L: AsRef<str>, E: From<ParseErrors>>(
+    lines: impl Iterator<Item = Result<L, E>>,
+    format: &InventoryFormat,
+    mut f: impl FnMut(Elf),
+) -> Result<(), E> {
+    let mut errors = Vec::new();
+    let mut new_elf = Elf::default();
+    let mut finish = |elf: Elf| {
+        if elf.name.is_some() || !elf.item_calories.is_empty() {
+            f(elf);
+        }
+    };
+    for (idx, line) in lines.enumerate() {
+        let line = line?;
+        let line = line.as_ref();
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match format.read_line(line) {
+            Ok(InventoryLine::Item(calories)) => new_elf.add_item(calories),
+            Ok(InventoryLine::Separator) => finish(std::mem::take(&mut new_elf)),
+            Ok(InventoryLine::Label(name)) => {
+                finish(std::mem::take(&mut new_elf));
+                new_elf.name = Some(name.to_owned());
+            }
+            Ok(InventoryLine::Comment) => {}
+            Err(e) => errors.push(e.on_line(idx + 1, line)),
+        }
+    }
+    // Add the last elf if for some reason we didn't end with an empty line
+    finish(new_elf);
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(ParseErrors(errors).into()),
+    }
+}
+
+/// Parses the inventories, reporting every line that can't be read.
+pub fn parse_inventory(s: &str, format: &InventoryFormat) -> Result<Vec<Elf>, ParseErrors> {
+    let mut elves = Vec::new();
+    each_elf::<_, ParseErrors>(s.lines().map(Ok), format, |elf| elves.push(elf))?;
+    Ok(elves)
+}
+
+/// Parses the inventories, one item per line with elves separated by an empty line.
+pub fn parse_elves(s: &str) -> Result<Vec<Elf>, ParseErrors> {
+    parse_inventory(s, &InventoryFormat::default())
+}
+
+/// The elves carrying the most calories out of all those seen, forgetting the rest.
+#[derive(Debug)]
+pub struct TopElves {
+    capacity: usize,
+    /// The top elves, with the one carrying the least on top, ready to be replaced.
+    heap: BinaryHeap<Reverse<Elf>>,
+    /// How many elves have been seen.
+    pub seen: usize,
+}
+
+impl TopElves {
+    /// Keeps the top `capacity` elves.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            heap: BinaryHeap::with_capacity(capacity + 1),
+            seen: 0,
+        }
+    }
+
+    pub fn add(&mut self, elf: Elf) {
+        self.seen += 1;
+        if self.heap.len() < self.capacity {
+            self.heap.push(Reverse(elf));
+        } else if let Some(mut least) = self.heap.peek_mut() {
+            if elf > least.0 {
+                *least = Reverse(elf);
+            }
+        }
+    }
+
+    /// The top elves, from the most calories carried to the least.
+    pub fn ranked(&self) -> Vec<&Elf> {
+        let mut ranked: Vec<_> = self.heap.iter().map(|Reverse(elf)| elf).collect();
+        ranked.sort_by(|a, b| b.cmp(a));
+        ranked
+    }
+
+    /// Total calories carried by the `n` elves carrying the most, which can't be more than were
+    /// kept.
+    pub fn total(&self, n: usize) -> usize {
+        self.ranked()
+            .iter()
+            .take(n)
+            .map(|elf| elf.total_calories)
+            .sum()
+    }
+}
+
+/// Reads the inventories a line at a time, keeping only the top `capacity` elves, so even huge
+/// inventories fit in memory.
+pub fn read_top_elves(
+    reader: impl BufRead,
+    format: &InventoryFormat,
+    capacity: usize,
+) -> Result<TopElves> {
+    let mut top = TopElves::new(capacity);
+    let lines = reader
+        .lines()
+        .map(|line| line.wrap_err("Couldn't read the inventory"));
+    each_elf::<_, Report>(lines, format, |elf| top.add(elf))?;
+    Ok(top)
+}
+
+/// Returns the elves ordered from the most calories carried to the least.
+pub fn ranked(elves: &[Elf]) -> Vec<&Elf> {
+    let mut ranked: Vec<_> = elves.iter().collect();
+    ranked.sort();
+    ranked.reverse();
+    ranked
+}
+
+/// Total calories carried by the `n` elves carrying the most.
+pub fn top_total(elves: &[Elf], n: usize) -> usize {
+    let mut top = BinaryHeap::with_capacity(n + 1);
+    for elf in elves {
+        top.push(Reverse(elf.total_calories));
+        if top.len() > n {
+            top.pop();
+        }
+    }
+    top.into_iter().map(|Reverse(total)| total).sum()
+}
+
+/// Plans at most `max_moves` moves of items between elves to even out what they carry, see
+/// [`balance::rebalance`].
+pub fn rebalance(elves: &[Elf], goal: Goal, max_moves: usize) -> Plan {
+    let inventories: Vec<_> = elves.iter().map(|e| e.item_calories.clone()).collect();
+    balance::rebalance(&inventories, goal, max_moves)
+}
+
+/// Percentiles reported in [`CalorieStats`].
+pub const PERCENTILES: [u8; 7] = [1, 10, 25, 50, 75, 90, 99];
+
+/// How many elves carry between `low` and `high` calories, inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bucket {
+    pub low: usize,
+    pub high: usize,
+    pub elves: usize,
+}
+
+/// What the elves carry, in numbers.  Elves are numbered from 1 in the order they're listed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalorieStats {
+    pub elves: usize,
+    pub items: usize,
+    pub mean: f64,
+    pub median: f64,
+    /// The total carried by an elf at each of the [`PERCENTILES`], by nearest rank.
+    pub percentiles: Vec<(u8, usize)>,
+    /// The range of totals split into equal buckets.
+    pub histogram: Vec<Bucket>,
+    /// Each elf's smallest and largest items, or `None` for an elf without any.
+    pub item_ranges: Vec<Option<(usize, usize)>>,
+    /// Elves carrying more than 1.5 interquartile ranges beyond the quartiles, and their totals.
+    pub outliers: Vec<(usize, usize)>,
+}
+
+impl CalorieStats {
+    /// Works out the stats of `elves`, with `buckets` buckets in the histogram, or `None` if there
+    /// are no elves.
+    pub fn new(elves: &[Elf], buckets: usize) -> Option<Self> {
+        let mut totals: Vec<usize> = elves.iter().map(|e| e.total_calories).collect();
+        totals.sort_unstable();
+        let (&least, &most) = (totals.first()?, totals.last()?);
+        let n = totals.len();
+
+        let percentile = |p: u8| totals[(p as usize * n).div_ceil(100).clamp(1, n) - 1];
+        let median = match n % 2 {
+            0 => (totals[n / 2 - 1] + totals[n / 2]) as f64 / 2.0,
+            _ => totals[n / 2] as f64,
+        };
+
+        let width = (most - least) / buckets.max(1) + 1;
+        let mut histogram: Vec<_> = (0..buckets.max(1))
+            .map(|b| Bucket {
+                low: least + b * width,
+                high: (least + (b + 1) * width - 1).min(most),
+                elves: 0,
+            })
+            .take_while(|b| b.low <= most)
+            .collect();
+        for total in &totals {
+            histogram[(total - least) / width].elves += 1;
+        }
+
+        let (q1, q3) = (percentile(25) as f64, percentile(75) as f64);
+        let fence = 1.5 * (q3 - q1);
+        let outliers = elves
+            .iter()
+            .enumerate()
+            .map(|(idx, elf)| (idx + 1, elf.total_calories))
+            .filter(|&(_, total)| (total as f64) < q1 - fence || (total as f64) > q3 + fence)
+            .collect();
+
+        Some(Self {
+            elves: n,
+            items: elves.iter().map(|e| e.item_calories.len()).sum(),
+            mean: totals.iter().sum::<usize>() as f64 / n as f64,
+            median,
+            percentiles: PERCENTILES.iter().map(|&p| (p, percentile(p))).collect(),
+            histogram,
+            item_ranges: elves
+                .iter()
+                .map(|e| e.smallest_item.zip(e.largest_item))
+                .collect(),
+            outliers,
+        })
+    }
+
+    /// The elf carrying the single largest item, and its calories.
+    pub fn largest_item(&self) -> Option<(usize, usize)> {
+        let items = self.item_ranges.iter().enumerate();
+        items
+            .filter_map(|(idx, range)| Some((idx + 1, range.as_ref()?.1)))
+            .max_by_key(|&(_, calories)| calories)
+    }
+
+    /// The elf carrying the single smallest item, and its calories.
+    pub fn smallest_item(&self) -> Option<(usize, usize)> {
+        let items = self.item_ranges.iter().enumerate();
+        items
+            .filter_map(|(idx, range)| Some((idx + 1, range.as_ref()?.0)))
+            .min_by_key(|&(_, calories)| calories)
+    }
+}
+
+impl Display for CalorieStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<16}{}", "Elves", self.elves)?;
+        writeln!(f, "{:<16}{}", "Items", self.items)?;
+        writeln!(f, "{:<16}{:.1}", "Mean", self.mean)?;
+        writeln!(f, "{:<16}{:.1}", "Median", self.median)?;
+        for (p, total) in &self.percentiles {
+            let suffix = match (p % 10, p % 100) {
+                (_, 11..=13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            };
+            writeln!(f, "{:<16}{total}", format!("{p}{suffix} percentile"))?;
+        }
+        if let Some((elf, calories)) = self.smallest_item() {
+            writeln!(f, "{:<16}{calories} (elf {elf})", "Smallest item")?;
+        }
+        if let Some((elf, calories)) = self.largest_item() {
+            writeln!(f, "{:<16}{calories} (elf {elf})", "Largest item")?;
+        }
+
+        let most = self.histogram.iter().map(|b| b.elves).max().unwrap_or(0);
+        let ranges: Vec<_> = self
+            .histogram
+            .iter()
+            .map(|b| format!("{}-{}", b.low, b.high))
+            .collect();
+        let width = ranges.iter().map(String::len).max().unwrap_or(0);
+        writeln!(f, "\n{:<width$}  Elves", "Calories")?;
+        for (range, bucket) in ranges.iter().zip(&self.histogram) {
+            let bar = "#".repeat((bucket.elves * 40).div_ceil(most.max(1)));
+            let line = format!("{range:<width$}  {:<5} {bar}", bucket.elves);
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        write!(f, "\nOutliers")?;
+        if self.outliers.is_empty() {
+            write!(f, "\n(none)")?;
+        }
+        for (elf, total) in &self.outliers {
+            write!(f, "\nElf {elf:<12}{total}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&CalorieStats> for Json {
+    fn from(stats: &CalorieStats) -> Self {
+        let percentiles: Vec<_> = stats
+            .percentiles
+            .iter()
+            .map(|&(p, total)| Json::object().with("percentile", p).with("total", total))
+            .collect();
+        let histogram: Vec<_> = stats
+            .histogram
+            .iter()
+            .map(|b| {
+                Json::object()
+                    .with("low", b.low)
+                    .with("high", b.high)
+                    .with("elves", b.elves)
+            })
+            .collect();
+        let item_ranges: Vec<_> = stats
+            .item_ranges
+            .iter()
+            .enumerate()
+            .map(|(idx, range)| {
+                Json::object()
+                    .with("elf", idx + 1)
+                    .with("smallest_item", range.map(|r| r.0))
+                    .with("largest_item", range.map(|r| r.1))
+            })
+            .collect();
+        let outliers: Vec<_> = stats
+            .outliers
+            .iter()
+            .map(|&(elf, total)| Json::object().with("elf", elf).with("total", total))
+            .collect();
+        Json::object()
+            .with("elves", stats.elves)
+            .with("items", stats.items)
+            .with("mean", stats.mean)
+            .with("median", stats.median)
+            .with("percentiles", percentiles)
+            .with("histogram", histogram)
+            .with("item_ranges", item_ranges)
+            .with("outliers", outliers)
+    }
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<Elf>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_elves(input)?)
+    }
+
+    fn part1(elves: &Self::Input) -> Result<usize> {
+        Ok(top_total(elves, 1))
+    }
+
+    fn part2(elves: &Self::Input) -> Result<usize> {
+        Ok(top_total(elves, 3))
+    }
+
+    fn details(elves: &Self::Input) -> Result<Json> {
+        let ranked: Vec<_> = ranked(elves)
+            .iter()
+            .map(|elf| {
+                Json::object()
+                    .with("items", elf.item_calories.clone())
+                    .with("total_calories", elf.total_calories)
+            })
+            .collect();
+        Ok(Json::object().with("ranked_elves", ranked))
+    }
+}
+
+impl Generator for Day1 {
+    /// `size` elves, each carrying one to ten snacks.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut s = String::new();
+        for idx in 0..size.max(1) {
+            if idx > 0 {
+                s.push('\n');
+            }
+            for _ in 0..rng.between(1, 10) {
+                s += &format!("{}\n", rng.between(1, 20_000));
+            }
+        }
+        s
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        let mut totals: Vec<usize> = input
+            .split("\n\n")
+            .map(|elf| elf.lines().map(|l| l.parse::<usize>().unwrap()).sum())
+            .collect();
+        totals.sort_unstable_by(|a, b| b.cmp(a));
+        [
+            Some(totals[0].to_string()),
+            Some(totals.iter().take(3).sum::<usize>().to_string()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day1>(Part::Both);
+    }
+
+    #[test]
+    fn ranking_keeps_every_elf_in_order() {
+        gen::check_generated::<Day1>(|_, elves| {
+            let ranked = ranked(elves);
+            assert_eq!(elves.len(), ranked.len());
+            assert!(ranked
+                .windows(2)
+                .all(|w| w[0].total_calories >= w[1].total_calories));
+            for elf in elves {
+                assert_eq!(elf.item_calories.iter().sum::<usize>(), elf.total_calories);
+            }
+        });
+    }
+
+    #[test]
+    fn streaming_keeps_the_top_elves() {
+        gen::check_generated::<Day1>(|input, elves| {
+            for n in [0, 1, 3, 5] {
+                let top = read_top_elves(input.as_bytes(), &InventoryFormat::default(), n).unwrap();
+                assert_eq!(elves.len(), top.seen);
+                assert_eq!(top_total(elves, n), top.total(n));
+                let expected: Vec<_> = ranked(elves).iter().map(|e| e.total_calories).collect();
+                let kept: Vec<_> = top.ranked().iter().map(|e| e.total_calories).collect();
+                assert_eq!(expected[..n.min(elves.len())], kept);
+            }
+        });
+    }
+
+    #[test]
+    fn streaming_reports_the_bad_line() {
+        let err =
+            read_top_elves("100\n\n2x0\n".as_bytes(), &InventoryFormat::default(), 3).unwrap_err();
+        let ParseErrors(errs) = err.downcast_ref::<ParseErrors>().unwrap();
+        assert_eq!((Some(3), "2x0"), (errs[0].line, errs[0].text.as_str()));
+    }
+
+    #[test]
+    fn tolerates_untidy_inventories() {
+        let format = InventoryFormat {
+            comment: "//".to_owned(),
+            separator: Some("---".to_owned()),
+        };
+        let elves = parse_inventory(
+            "// snacks\r\n1000\r\n 2000 \r\n \t \r\nAlice:\r\n3000\r\n---\r\n\r\n\r\n4000\r\n",
+            &format,
+        )
+        .unwrap();
+        let summary: Vec<_> = elves
+            .iter()
+            .map(|e| (e.name.as_deref(), e.total_calories))
+            .collect();
+        assert_eq!(
+            vec![(None, 3000), (Some("Alice"), 3000), (None, 4000)],
+            summary
+        );
+    }
+
+    #[test]
+    fn reports_every_bad_line() {
+        let ParseErrors(errs) = parse_elves("100\n-5\n\nabc\n200\n1.5").unwrap_err();
+        let found: Vec<_> = errs.iter().map(|e| (e.line, e.message.as_str())).collect();
+        assert_eq!(
+            vec![
+                (Some(2), "Calories can't be negative"),
+                (Some(4), "Calories must be a whole number"),
+                (Some(6), "Calories must be a whole number"),
+            ],
+            found
+        );
+    }
+
+    #[test]
+    fn stats_of_the_example() {
+        let elves =
+            parse_elves("1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n")
+                .unwrap();
+        let stats = CalorieStats::new(&elves, 3).unwrap();
+        assert_eq!((5, 10), (stats.elves, stats.items));
+        assert_eq!((11000.0, 10000.0), (stats.mean, stats.median));
+        assert_eq!(Some(&(50, 10000)), stats.percentiles.get(3));
+        assert_eq!(Some((1, 1000)), stats.smallest_item());
+        assert_eq!(Some((5, 10000)), stats.largest_item());
+        let counts: Vec<_> = stats.histogram.iter().map(|b| b.elves).collect();
+        assert_eq!(vec![3, 1, 1], counts);
+        assert_eq!(vec![(4, 24000)], stats.outliers);
+        assert_eq!(None, CalorieStats::new(&[], 3));
+    }
+
+    #[test]
+    fn histogram_counts_every_elf() {
+        gen::check_generated::<Day1>(|_, elves| {
+            for buckets in [1, 7, 10] {
+                let stats = CalorieStats::new(elves, buckets).unwrap();
+                assert!(stats.histogram.len() <= buckets);
+                assert_eq!(elves.len(), stats.histogram.iter().map(|b| b.elves).sum());
+                let (p1, p99) = (stats.percentiles[0].1, stats.percentiles[6].1);
+                assert!(p1 as f64 <= stats.median && stats.median <= p99 as f64);
+            }
+        });
+    }
+
+    #[test]
+    fn more_elves_carry_more() {
+        gen::check_generated::<Day1>(|_, elves| {
+            for n in 0..elves.len() {
+                assert!(top_total(elves, n) <= top_total(elves, n + 1));
+            }
+            let everything: usize = elves.iter().map(|e| e.total_calories).sum();
+            assert_eq!(everything, top_total(elves, elves.len()));
+        });
+    }
+
+    #[test]
+    fn rebalancing_only_moves_items_around() {
+        gen::check_generated::<Day1>(|_, elves| {
+            for goal in [Goal::Max, Goal::Spread] {
+                let plan = rebalance(elves, goal, 5);
+                assert!(plan.moves.len() <= 5);
+                let mut loads = plan.before.clone();
+                for m in &plan.moves {
+                    assert!(elves.len() > m.to && m.from != m.to);
+                    loads[m.from] -= m.item;
+                    loads[m.to] += m.item;
+                }
+                assert_eq!(loads, plan.after);
+                let (max, spread) = (plan.max(), plan.spread());
+                match goal {
+                    Goal::Max => assert!(max.1 <= max.0),
+                    Goal::Spread => assert!(spread.1 <= spread.0),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,254 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use color_eyre::Result;
+
+use crate::gen::{Generator, Rng};
+use crate::json::Json;
+use crate::parse::{self, ParseError};
+use crate::Solution;
+
+pub enum Inst {
+    Addx { x: i64 },
+    Noop,
+}
+
+impl FromStr for Inst {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(' ') {
+            None if s == "noop" => Ok(Self::Noop),
+            Some(("addx", num)) => Ok(Self::Addx {
+                x: parse::number(num, "addx needs a whole number").map_err(|e| e.within(s, num))?,
+            }),
+            None if s == "addx" => Err(ParseError::at("addx needs a number", s, &s[s.len()..])),
+            _ => Err(ParseError::new("Instructions are noop or addx N", s)),
+        }
+    }
+}
+
+pub struct Cpu {
+    pub reg: i64,
+    pub cycles: usize,
+    pub signal_strengths: Vec<i64>,
+    pub crt_rows: Vec<Vec<char>>,
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cpu {
+    pub fn new() -> Self {
+        Self {
+            reg: 1,
+            cycles: 0,
+            signal_strengths: Vec::new(),
+            crt_rows: vec![Vec::new()],
+        }
+    }
+
+    fn add_pixel(&mut self, c: bool) {
+        if self.crt_rows.last().unwrap().len() == 40 {
+            self.crt_rows.push(Vec::new());
+        }
+        if c {
+            self.crt_rows.last_mut().unwrap().push('#');
+        } else {
+            self.crt_rows.last_mut().unwrap().push('.');
+        }
+    }
+
+    fn is_pixel_showing(&self, cycles: usize) -> bool {
+        let current_horiz = (cycles % 40) as i64;
+        current_horiz == self.reg || current_horiz == self.reg - 1 || current_horiz == self.reg + 1
+    }
+
+    pub fn apply(&mut self, inst: &Inst) {
+        match inst {
+            Inst::Addx { x } => {
+                // two cycles
+                self.add_pixel(self.is_pixel_showing(self.cycles));
+                self.add_pixel(self.is_pixel_showing(self.cycles + 1));
+                if (self.cycles + 21).is_multiple_of(40) {
+                    // Signal strength halfway through this cycle.
+                    self.signal_strengths
+                        .push((self.cycles as i64 + 1) * self.reg);
+                } else if (self.cycles + 22).is_multiple_of(40) {
+                    // Signal strength at the second cycle of this, but _during_, so the reg is
+                    // still not updated.
+                    self.signal_strengths
+                        .push((self.cycles as i64 + 2) * self.reg);
+                }
+                self.cycles += 2;
+                self.reg += x;
+            }
+            Inst::Noop => {
+                self.add_pixel(self.is_pixel_showing(self.cycles));
+                if (self.cycles + 21).is_multiple_of(40) {
+                    // Signal strength at the end of the noop.
+                    self.signal_strengths
+                        .push((self.cycles as i64 + 1) * self.reg);
+                }
+                self.cycles += 1;
+            }
+        }
+    }
+
+    /// Sum of the signal strengths during the 20th, 60th, and so on up to the 220th cycles, the
+    /// only ones the puzzle asks about.
+    pub fn sum_signal_strengths(&self) -> i64 {
+        self.signal_strengths.iter().take(6).sum()
+    }
+
+    pub fn crt(&self) -> Crt {
+        Crt {
+            rows: self
+                .crt_rows
+                .iter()
+                .map(|v| v.iter().collect::<String>())
+                .collect(),
+        }
+    }
+}
+
+/// The picture drawn on the CRT, one string per row of pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crt {
+    pub rows: Vec<String>,
+}
+
+impl Display for Crt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, row) in self.rows.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Crt> for Json {
+    fn from(crt: Crt) -> Self {
+        crt.rows.into()
+    }
+}
+
+/// Runs every instruction on a fresh CPU.
+pub fn run(insts: &[Inst]) -> Cpu {
+    let mut cpu = Cpu::new();
+    for inst in insts {
+        cpu.apply(inst);
+    }
+    cpu
+}
+
+pub fn parse_instrs(s: &str) -> Result<Vec<Inst>, ParseError> {
+    parse::parse_lines(s, Inst::from_str)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Inst>;
+    type Part1 = i64;
+    type Part2 = Crt;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_instrs(input)?)
+    }
+
+    fn part1(insts: &Self::Input) -> Result<i64> {
+        Ok(run(insts).sum_signal_strengths())
+    }
+
+    fn part2(insts: &Self::Input) -> Result<Crt> {
+        Ok(run(insts).crt())
+    }
+
+    fn details(insts: &Self::Input) -> Result<Json> {
+        let cpu = run(insts);
+        Ok(Json::object()
+            .with("cycles", cpu.cycles)
+            .with("x", cpu.reg)
+            .with("signal_strengths", cpu.signal_strengths))
+    }
+}
+
+impl Generator for Day10 {
+    /// A program of `size` instructions.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1))
+            .map(|_| match rng.one_in(3) {
+                true => "noop\n".to_owned(),
+                false => format!("addx {}\n", rng.between(0, 40) as i64 - 20),
+            })
+            .collect()
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        // The value of X during each cycle.
+        let mut xs = Vec::new();
+        let mut x = 1;
+        for line in input.lines() {
+            xs.push(x);
+            if let Some(v) = line.strip_prefix("addx ") {
+                xs.push(x);
+                x += v.parse::<i64>().unwrap();
+            }
+        }
+        let strengths: i64 = [20, 60, 100, 140, 180, 220]
+            .iter()
+            .filter(|&&cycle| cycle <= xs.len())
+            .map(|&cycle| cycle as i64 * xs[cycle - 1])
+            .sum();
+        let pixels: Vec<char> = xs
+            .iter()
+            .enumerate()
+            .map(|(cycle, x)| match ((cycle % 40) as i64 - x).abs() <= 1 {
+                true => '#',
+                false => '.',
+            })
+            .collect();
+        let rows: Vec<String> = pixels.chunks(40).map(|r| r.iter().collect()).collect();
+        [Some(strengths.to_string()), Some(rows.join("\n"))]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day10>(Part::Both);
+    }
+
+    #[test]
+    fn only_sums_signal_strengths_up_to_the_220th_cycle() {
+        let insts = parse_instrs(&"noop\n".repeat(280)).unwrap();
+        let cpu = run(&insts);
+        // X stays at 1, and the 260th cycle is measured, but isn't part of the answer.
+        assert_eq!(vec![20, 60, 100, 140, 180, 220, 260], cpu.signal_strengths);
+        assert_eq!(720, Day10::part1(&insts).unwrap());
+    }
+
+    #[test]
+    fn crt_draws_a_pixel_each_cycle() {
+        gen::check_generated::<Day10>(|_, insts| {
+            let cpu = run(insts);
+            let rows = cpu.crt().rows;
+            assert_eq!(cpu.cycles, rows.iter().map(String::len).sum::<usize>());
+            assert!(rows[..rows.len() - 1].iter().all(|r| r.len() == 40));
+        });
+    }
+}
@@ -0,0 +1,328 @@
+use color_eyre::Result;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::gen::{Generator, Rng};
+use crate::json::Json;
+use crate::parse::{self, ParseError};
+use crate::{trace, Solution};
+
+pub mod analysis;
+pub mod rules;
+pub mod tournament;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Shape {
+    Scissors,
+    Paper,
+    Rock,
+}
+
+impl FromStr for Shape {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Shape::Rock),
+            "B" => Ok(Shape::Paper),
+            "C" => Ok(Shape::Scissors),
+            _ => Err(ParseError::new("Their shape is one of A, B or C", s)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GameResult {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl GameResult {
+    pub fn score(&self) -> u64 {
+        match self {
+            GameResult::Win => 6,
+            GameResult::Draw => 3,
+            GameResult::Loss => 0,
+        }
+    }
+}
+
+/// The letter in the guide's second column, which means either a shape or a result.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Column {
+    X,
+    Y,
+    Z,
+}
+
+impl Column {
+    /// The column read as the shape to throw, as we first thought.
+    pub fn shape(&self) -> Shape {
+        match self {
+            Column::X => Shape::Rock,
+            Column::Y => Shape::Paper,
+            Column::Z => Shape::Scissors,
+        }
+    }
+
+    /// The column read as how the round should end, as the elf meant.
+    pub fn result(&self) -> GameResult {
+        match self {
+            Column::X => GameResult::Loss,
+            Column::Y => GameResult::Draw,
+            Column::Z => GameResult::Win,
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "X" => Ok(Column::X),
+            "Y" => Ok(Column::Y),
+            "Z" => Ok(Column::Z),
+            _ => Err(ParseError::new("The second column is one of X, Y or Z", s)),
+        }
+    }
+}
+
+impl Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// How to read the guide's second column.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Reading {
+    /// As the shape to throw, for part one.
+    Shape,
+    /// As how the round should end, for part two.
+    Result,
+}
+
+impl Reading {
+    pub const BOTH: [Reading; 2] = [Reading::Shape, Reading::Result];
+}
+
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
+
+    /// Returns the shape you throw to get the result given when playing against this shape.
+    pub fn for_result(&self, result: GameResult) -> Self {
+        use GameResult::*;
+        use Shape::*;
+        match (self, result) {
+            (x, Draw) => *x,
+            (Scissors, Win) => Rock,
+            (Rock, Win) => Paper,
+            (Paper, Win) => Scissors,
+            (Scissors, Loss) => Paper,
+            (Rock, Loss) => Scissors,
+            (Paper, Loss) => Rock,
+        }
+    }
+
+    pub fn value(&self) -> u64 {
+        match self {
+            Shape::Scissors => 3,
+            Shape::Paper => 2,
+            Shape::Rock => 1,
+        }
+    }
+
+    pub fn versus(&self, opp: &Self) -> GameResult {
+        use GameResult::*;
+        use Shape::*;
+        match (self, opp) {
+            (Scissors, Paper) => Win,
+            (Paper, Rock) => Win,
+            (Rock, Scissors) => Win,
+            (x, y) if x == y => Draw,
+            _ => Loss,
+        }
+    }
+
+    /// Returns the score you receive if you play against opp
+    pub fn score(&self, opp: &Self) -> u64 {
+        self.value() + self.versus(opp).score()
+    }
+}
+
+/// One round of the strategy guide, as written.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Round {
+    pub theirs: Shape,
+    pub column: Column,
+}
+
+impl Round {
+    /// What we throw this round when reading the guide `reading`'s way.
+    pub fn my_shape(&self, reading: Reading) -> Shape {
+        match reading {
+            Reading::Shape => self.column.shape(),
+            Reading::Result => self.theirs.for_result(self.column.result()),
+        }
+    }
+
+    pub fn score(&self, reading: Reading) -> u64 {
+        self.my_shape(reading).score(&self.theirs)
+    }
+}
+
+/// The strategy guide, which can be read either way.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StrategyGuide {
+    pub rounds: Vec<Round>,
+}
+
+impl StrategyGuide {
+    /// Our total score from following the guide, read `reading`'s way.
+    pub fn score(&self, reading: Reading) -> u64 {
+        let mut total_score = 0;
+        for round in &self.rounds {
+            let (my_throw, their_throw) = (round.my_shape(reading), round.theirs);
+            let score = my_throw.score(&their_throw);
+            trace!("Played {my_throw:?} vs {their_throw:?} and got {score} points");
+            total_score += score;
+        }
+        total_score
+    }
+
+    /// Our total scores reading the guide each way, in the order of [`Reading::BOTH`].
+    pub fn scores(&self) -> [u64; 2] {
+        Reading::BOTH.map(|reading| self.score(reading))
+    }
+}
+
+/// Parses the strategy guide as their throw and a column letter each round.
+pub fn parse_guide(s: &str) -> Result<StrategyGuide, ParseError> {
+    let rounds = parse::parse_lines(s, |line| {
+        let (their_throw, column) = line.split_once(' ').ok_or_else(|| {
+            ParseError::at(
+                "Expected their shape and X, Y or Z",
+                line,
+                &line[line.len()..],
+            )
+        })?;
+        Ok(Round {
+            theirs: their_throw
+                .parse()
+                .map_err(|e: ParseError| e.within(line, their_throw))?,
+            column: column
+                .parse()
+                .map_err(|e: ParseError| e.within(line, column))?,
+        })
+    })?;
+    Ok(StrategyGuide { rounds })
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = StrategyGuide;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_guide(input)?)
+    }
+
+    fn part1(guide: &Self::Input) -> Result<u64> {
+        Ok(guide.score(Reading::Shape))
+    }
+
+    fn part2(guide: &Self::Input) -> Result<u64> {
+        Ok(guide.score(Reading::Result))
+    }
+
+    fn details(guide: &Self::Input) -> Result<Json> {
+        let rounds: Vec<_> = guide
+            .rounds
+            .iter()
+            .map(|round| {
+                let result = round.column.result();
+                Json::object()
+                    .with("their_shape", format!("{:?}", round.theirs))
+                    .with("column", round.column.to_string())
+                    .with(
+                        "part1_shape",
+                        format!("{:?}", round.my_shape(Reading::Shape)),
+                    )
+                    .with("part1_score", round.score(Reading::Shape))
+                    .with("part2_result", format!("{result:?}"))
+                    .with(
+                        "part2_shape",
+                        format!("{:?}", round.my_shape(Reading::Result)),
+                    )
+                    .with("part2_score", round.score(Reading::Result))
+            })
+            .collect();
+        Ok(Json::object().with("rounds", rounds))
+    }
+}
+
+impl Generator for Day2 {
+    /// `size` rounds.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1))
+            .map(|_| {
+                format!(
+                    "{} {}\n",
+                    rng.pick(&['A', 'B', 'C']),
+                    rng.pick(&['X', 'Y', 'Z'])
+                )
+            })
+            .collect()
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        let (mut part1, mut part2) = (0, 0);
+        for line in input.lines() {
+            let bytes = line.as_bytes();
+            let (them, me) = ((bytes[0] - b'A') as u64, (bytes[2] - b'X') as u64);
+            // Counting rock, paper, scissors from zero, each beats the one before it.
+            part1 += me + 1 + [3, 6, 0][((me + 3 - them) % 3) as usize];
+            // Read as lose, draw, win instead.
+            part2 += (them + me + 2) % 3 + 1 + 3 * me;
+        }
+        [Some(part1.to_string()), Some(part2.to_string())]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day2>(Part::Both);
+    }
+
+    #[test]
+    fn playing_for_a_result_gets_it() {
+        gen::check_generated::<Day2>(|_, guide| {
+            for round in &guide.rounds {
+                let my_throw = round.my_shape(Reading::Result);
+                assert_eq!(round.column.result(), my_throw.versus(&round.theirs));
+                assert!((1..=9).contains(&round.score(Reading::Result)));
+            }
+        });
+    }
+
+    #[test]
+    fn reads_the_guide_both_ways() {
+        let guide = parse_guide("A Y\nB X\nC Z").unwrap();
+        assert_eq!([15, 12], guide.scores());
+        assert_eq!(Column::Y, guide.rounds[0].column);
+        assert_eq!(Shape::Paper, guide.rounds[0].my_shape(Reading::Shape));
+        assert_eq!(Shape::Rock, guide.rounds[0].my_shape(Reading::Result));
+        assert!(parse_guide("X Y").is_err());
+        assert!(parse_guide("A C").is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! How well anyone could do against the opponent's throws in a strategy guide.
+
+use std::fmt::Display;
+
+use super::{GameResult, Reading, Shape, StrategyGuide};
+use crate::json::Json;
+
+const RESULTS: [GameResult; 3] = [GameResult::Win, GameResult::Draw, GameResult::Loss];
+
+/// The choices against one of the opponent's throws.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundChoices {
+    pub theirs: Shape,
+    /// The throw that scores the most, and how the round ends with it.
+    pub best: Shape,
+    pub best_result: GameResult,
+    pub max: u64,
+    pub min: u64,
+    /// The scores of every possible throw added up, for working out the average.
+    pub all: u64,
+}
+
+impl RoundChoices {
+    pub fn new(theirs: Shape) -> Self {
+        let scores = RESULTS.map(|result| {
+            let mine = theirs.for_result(result);
+            (mine.value() + result.score(), mine, result)
+        });
+        let &(max, best, best_result) = scores.iter().max_by_key(|s| s.0).unwrap();
+        Self {
+            theirs,
+            best,
+            best_result,
+            max,
+            min: scores.iter().map(|s| s.0).min().unwrap(),
+            all: scores.iter().map(|s| s.0).sum(),
+        }
+    }
+}
+
+/// The best, worst and average scores against the guide's opponent, and how the guide compares.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub rounds: Vec<RoundChoices>,
+    pub max: u64,
+    pub min: u64,
+    /// The score throwing at random would get on average.
+    pub expected: f64,
+    /// The guide's own scores, in the order of [`Reading::BOTH`].
+    pub guide: [u64; 2],
+}
+
+pub fn analyse(guide: &StrategyGuide) -> Analysis {
+    let rounds: Vec<_> = guide
+        .rounds
+        .iter()
+        .map(|r| RoundChoices::new(r.theirs))
+        .collect();
+    let all: u64 = rounds.iter().map(|r| r.all).sum();
+    Analysis {
+        max: rounds.iter().map(|r| r.max).sum(),
+        min: rounds.iter().map(|r| r.min).sum(),
+        expected: all as f64 / Shape::ALL.len() as f64,
+        guide: guide.scores(),
+        rounds,
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<24}{}", "Best possible", self.max)?;
+        writeln!(f, "{:<24}{}", "Worst possible", self.min)?;
+        writeln!(f, "{:<24}{:.1}", "Throwing at random", self.expected)?;
+        for (reading, score) in Reading::BOTH.iter().zip(self.guide) {
+            let label = match reading {
+                Reading::Shape => "Guide read as shapes",
+                Reading::Result => "Guide read as results",
+            };
+            writeln!(f, "{label:<24}{score}")?;
+        }
+        write!(f, "\nBest throws:")?;
+        for (idx, round) in self.rounds.iter().enumerate() {
+            write!(
+                f,
+                "\n{:>6}  {:?} against {:?} to {:?} for {}",
+                idx + 1,
+                round.best,
+                round.theirs,
+                round.best_result,
+                round.max
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&Analysis> for Json {
+    fn from(analysis: &Analysis) -> Self {
+        let rounds: Vec<_> = analysis
+            .rounds
+            .iter()
+            .map(|r| {
+                Json::object()
+                    .with("their_shape", format!("{:?}", r.theirs))
+                    .with("best_shape", format!("{:?}", r.best))
+                    .with("result", format!("{:?}", r.best_result))
+                    .with("max", r.max)
+                    .with("min", r.min)
+            })
+            .collect();
+        Json::object()
+            .with("max", analysis.max)
+            .with("min", analysis.min)
+            .with("expected", analysis.expected)
+            .with("part1", analysis.guide[0])
+            .with("part2", analysis.guide[1])
+            .with("rounds", rounds)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day2::{parse_guide, Day2};
+    use crate::gen;
+
+    #[test]
+    fn analyses_the_example() {
+        let analysis = analyse(&parse_guide("A Y\nB X\nC Z").unwrap());
+        // Paper beats rock for 8, scissors beats paper for 9, rock beats scissors for 7.
+        assert_eq!(8 + 9 + 7, analysis.max);
+        let best: Vec<_> = analysis.rounds.iter().map(|r| r.best).collect();
+        assert_eq!(vec![Shape::Paper, Shape::Scissors, Shape::Rock], best);
+        // Scissors loses to rock for 3, rock to paper for 1, paper to scissors for 2.
+        assert_eq!(3 + 1 + 2, analysis.min);
+        // Every throw is worth 1 to 3, and the results 0, 3 and 6, whatever they throw.
+        assert_eq!(3.0 * (2.0 + 3.0), analysis.expected);
+        assert_eq!([15, 12], analysis.guide);
+    }
+
+    #[test]
+    fn guide_is_between_best_and_worst() {
+        gen::check_generated::<Day2>(|_, guide| {
+            let analysis = analyse(guide);
+            for score in analysis.guide {
+                assert!((analysis.min..=analysis.max).contains(&score));
+            }
+            for round in &analysis.rounds {
+                assert_eq!(round.best_result, round.best.versus(&round.theirs));
+                let best = Shape::ALL.iter().map(|s| s.score(&round.theirs)).max();
+                assert_eq!(Some(round.max), best);
+            }
+        });
+    }
+}
@@ -0,0 +1,413 @@
+//! Rock-paper-scissors games other than the usual one, defined by their rules.
+//!
+//! A game is written one rule per line, with `#` starting a comment:
+//!
+//! ```text
+//! # Each shape has a name, a value, and any letters it goes by in strategy guides.
+//! shape Rock 1 A X
+//! shape Paper 2 B Y
+//! shape Scissors 3 C Z
+//! # The first shape beats each of the others.
+//! beats Rock Scissors
+//! beats Paper Rock
+//! beats Scissors Paper
+//! # What each result is worth, if not 6, 3 and 0.
+//! score win 6
+//! ```
+//!
+//! Every pair of different shapes must have exactly one winner, and every shape must beat at least
+//! one shape and be beaten by at least one, so there's always a throw for any result. That takes
+//! at least three shapes.
+
+use std::str::FromStr;
+
+use super::{Column, GameResult, Reading, Shape};
+use crate::parse::{self, ParseError, ParseErrors};
+
+/// The rules of the game in the puzzle.
+pub const STANDARD: &str = "shape Rock 1 A X
+shape Paper 2 B Y
+shape Scissors 3 C Z
+beats Rock Scissors
+beats Paper Rock
+beats Scissors Paper
+";
+
+/// The index of one of a [`Game`]'s shapes.
+pub type ShapeId = usize;
+
+/// A game of rock-paper-scissors with any number of shapes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    names: Vec<String>,
+    values: Vec<u64>,
+    /// Other names for each shape, for reading strategy guides.
+    aliases: Vec<(String, ShapeId)>,
+    /// `beats[a][b]` is whether shape `a` beats shape `b`.
+    beats: Vec<Vec<bool>>,
+    /// What a win, draw and loss are worth.
+    scores: [u64; 3],
+}
+
+fn result_idx(result: GameResult) -> usize {
+    match result {
+        GameResult::Win => 0,
+        GameResult::Draw => 1,
+        GameResult::Loss => 2,
+    }
+}
+
+impl Game {
+    /// The game in the puzzle, which plays the same as [`Shape`].
+    pub fn standard() -> Self {
+        STANDARD.parse().expect("The standard game is well-formed")
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = ShapeId> {
+        0..self.names.len()
+    }
+
+    pub fn name(&self, shape: ShapeId) -> &str {
+        &self.names[shape]
+    }
+
+    pub fn value(&self, shape: ShapeId) -> u64 {
+        self.values[shape]
+    }
+
+    /// Finds a shape by its name or one of its letters.
+    pub fn shape(&self, name: &str) -> Option<ShapeId> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .or_else(|| self.aliases.iter().find(|(a, _)| a == name).map(|a| a.1))
+    }
+
+    /// The shape in this game with the same name as `shape`, if there is one.
+    pub fn shape_of(&self, shape: Shape) -> Option<ShapeId> {
+        self.shape(&format!("{shape:?}"))
+    }
+
+    /// How the round ends for whoever throws `mine` against `theirs`.
+    pub fn versus(&self, mine: ShapeId, theirs: ShapeId) -> GameResult {
+        if mine == theirs {
+            GameResult::Draw
+        } else if self.beats[mine][theirs] {
+            GameResult::Win
+        } else {
+            GameResult::Loss
+        }
+    }
+
+    /// What `result` is worth.
+    pub fn result_score(&self, result: GameResult) -> u64 {
+        self.scores[result_idx(result)]
+    }
+
+    /// The score for throwing `mine` against `theirs`, as [`Shape::score`].
+    pub fn score(&self, mine: ShapeId, theirs: ShapeId) -> u64 {
+        self.value(mine) + self.result_score(self.versus(mine, theirs))
+    }
+
+    /// The shape to throw against `theirs` to get `result`, picking the one worth the most when
+    /// there's a choice, as [`Shape::for_result`].
+    pub fn for_result(&self, theirs: ShapeId, result: GameResult) -> ShapeId {
+        self.shapes()
+            .filter(|&mine| self.versus(mine, theirs) == result)
+            .max_by_key(|&mine| self.value(mine))
+            .expect("Well-formed games always have a throw for every result")
+    }
+
+    /// Our total score from following a strategy guide for this game, read `reading`'s way. The
+    /// guide has their shape and then ours, or X, Y or Z for the result, on each line.
+    pub fn score_guide(&self, guide: &str, reading: Reading) -> Result<u64, ParseError> {
+        let scores = parse::parse_lines(guide, |line| {
+            let (theirs, column) = line
+                .split_once(' ')
+                .ok_or_else(|| ParseError::at("Expected two shapes", line, &line[line.len()..]))?;
+            let find = |name| {
+                self.shape(name)
+                    .ok_or_else(|| ParseError::at("There's no shape by that name", line, name))
+            };
+            let theirs = find(theirs)?;
+            let mine = match reading {
+                Reading::Shape => find(column)?,
+                Reading::Result => {
+                    let column: Column = column
+                        .parse()
+                        .map_err(|e: ParseError| e.within(line, column))?;
+                    self.for_result(theirs, column.result())
+                }
+            };
+            Ok(self.score(mine, theirs))
+        })?;
+        Ok(scores.into_iter().sum())
+    }
+}
+
+impl FromStr for Game {
+    type Err = ParseErrors;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut game = Game {
+            names: Vec::new(),
+            values: Vec::new(),
+            aliases: Vec::new(),
+            beats: Vec::new(),
+            scores: [6, 3, 0],
+        };
+        // Where each shape and each `beats` rule came from, to point at them if they're wrong.
+        let mut shape_lines = Vec::new();
+        let mut rules = Vec::new();
+        let mut errors = Vec::new();
+
+        for (idx, line) in s.lines().enumerate() {
+            let rule = line.split('#').next().unwrap_or_default();
+            let words: Vec<_> = rule.split_whitespace().collect();
+            let wrong = |message: &str, word: &str| ParseError::at(message, line, word);
+            let read = match words.as_slice() {
+                [] => Ok(()),
+                ["shape", name, value, aliases @ ..] => {
+                    let taken = |n: &&str| game.shape(n).is_some() || *n == *name;
+                    if game.shape(name).is_some() {
+                        Err(wrong("There's already a shape by that name", name))
+                    } else if let Some(alias) = aliases.iter().find(|a| taken(a)) {
+                        Err(wrong("That name is already taken", alias))
+                    } else {
+                        parse::number(value, "Values are whole numbers")
+                            .map_err(|e| e.within(line, value))
+                            .map(|value| {
+                                let id = game.names.len();
+                                game.names.push(name.to_string());
+                                game.values.push(value);
+                                game.aliases
+                                    .extend(aliases.iter().map(|a| (a.to_string(), id)));
+                                shape_lines.push((idx + 1, line));
+                            })
+                    }
+                }
+                ["beats", winner, losers @ ..] if !losers.is_empty() => {
+                    rules.extend(losers.iter().map(|loser| (idx + 1, line, *winner, *loser)));
+                    Ok(())
+                }
+                ["score", result, points] => {
+                    let result = match *result {
+                        "win" => Ok(GameResult::Win),
+                        "draw" => Ok(GameResult::Draw),
+                        "loss" => Ok(GameResult::Loss),
+                        _ => Err(wrong("Results are win, draw or loss", result)),
+                    };
+                    result.and_then(|result| {
+                        game.scores[result_idx(result)] =
+                            parse::number(points, "Scores are whole numbers")
+                                .map_err(|e| e.within(line, points))?;
+                        Ok(())
+                    })
+                }
+                [keyword, ..] => Err(wrong(
+                    "Expected `shape NAME VALUE [LETTERS...]`, `beats WINNER LOSERS...` or \
+                     `score RESULT POINTS`",
+                    keyword,
+                )),
+            };
+            if let Err(e) = read {
+                errors.push(e.on_line(idx + 1, line));
+            }
+        }
+
+        // Shapes can be used before they're declared, so the rules are only read now.
+        let count = game.names.len();
+        game.beats = vec![vec![false; count]; count];
+        for (line_num, line, winner, loser) in rules {
+            let find = |name| {
+                game.shape(name).ok_or_else(|| {
+                    ParseError::at("There's no shape by that name", line, name)
+                        .on_line(line_num, line)
+                })
+            };
+            match (find(winner), find(loser)) {
+                (Ok(winner), Ok(loser)) if winner == loser => errors.push(
+                    ParseError::at("A shape can't beat itself", line, line).on_line(line_num, line),
+                ),
+                (Ok(winner), Ok(loser)) if game.beats[loser][winner] => {
+                    errors.push(
+                        ParseError::at(
+                            format!("{} already beats {}", game.names[loser], game.names[winner]),
+                            line,
+                            line,
+                        )
+                        .on_line(line_num, line),
+                    );
+                }
+                (Ok(winner), Ok(loser)) => game.beats[winner][loser] = true,
+                (winner, loser) => errors.extend(winner.err().into_iter().chain(loser.err())),
+            }
+        }
+
+        if count < 3 && errors.is_empty() {
+            errors.push(ParseError::new("A game needs at least three shapes", "").on_line(1, ""));
+        }
+        for a in 0..count {
+            for (b, &(line_num, line)) in shape_lines.iter().enumerate().skip(a + 1) {
+                if !game.beats[a][b] && !game.beats[b][a] {
+                    errors.push(
+                        ParseError::new(
+                            format!(
+                                "Neither {} nor {} beats the other",
+                                game.names[a], game.names[b]
+                            ),
+                            line,
+                        )
+                        .on_line(line_num, line),
+                    );
+                }
+            }
+        }
+
+        // Only worth checking once every pair has a winner, or it's the missing winners to blame.
+        if errors.is_empty() {
+            for (shape, &(line_num, line)) in shape_lines.iter().enumerate() {
+                let name = &game.names[shape];
+                let mut problems = Vec::new();
+                if !game.beats[shape].contains(&true) {
+                    problems.push(format!("{name} doesn't beat any shape"));
+                }
+                if !game.beats.iter().any(|beats| beats[shape]) {
+                    problems.push(format!("No shape beats {name}"));
+                }
+                errors.extend(
+                    problems
+                        .into_iter()
+                        .map(|message| ParseError::new(message, line).on_line(line_num, line)),
+                );
+            }
+        }
+
+        errors.sort_by_key(|e| e.line);
+        match errors.is_empty() {
+            true => Ok(game),
+            false => Err(ParseErrors(errors)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day2::{parse_guide, Day2};
+    use crate::gen;
+
+    const RPSLS: &str = include_str!("../../inputs/games/rpsls.txt");
+
+    #[test]
+    fn standard_game_plays_like_shapes() {
+        let game = Game::standard();
+        let shapes = [Shape::Rock, Shape::Paper, Shape::Scissors];
+        for mine in shapes {
+            let id = game.shape_of(mine).unwrap();
+            assert_eq!(mine.value(), game.value(id));
+            for theirs in shapes {
+                let their_id = game.shape_of(theirs).unwrap();
+                assert_eq!(mine.versus(&theirs), game.versus(id, their_id));
+                assert_eq!(mine.score(&theirs), game.score(id, their_id));
+            }
+            for result in [GameResult::Win, GameResult::Draw, GameResult::Loss] {
+                let expected = game.shape_of(mine.for_result(result));
+                assert_eq!(expected, Some(game.for_result(id, result)));
+            }
+        }
+    }
+
+    #[test]
+    fn standard_game_scores_guides_like_day2() {
+        gen::check_generated::<Day2>(|input, guide| {
+            let game = Game::standard();
+            for reading in Reading::BOTH {
+                assert_eq!(
+                    guide.score(reading),
+                    game.score_guide(input, reading).unwrap()
+                );
+            }
+        });
+        assert_eq!(
+            parse_guide("A Y").unwrap().scores(),
+            Reading::BOTH.map(|r| Game::standard().score_guide("A Y", r).unwrap())
+        );
+    }
+
+    #[test]
+    fn plays_rock_paper_scissors_lizard_spock() {
+        let game: Game = RPSLS.parse().unwrap();
+        let id = |name| game.shape(name).unwrap();
+        assert_eq!(GameResult::Win, game.versus(id("Spock"), id("Scissors")));
+        assert_eq!(GameResult::Loss, game.versus(id("Lizard"), id("Rock")));
+        assert_eq!(GameResult::Win, game.versus(id("Lizard"), id("Spock")));
+        // Both Paper and Spock beat Rock, and Spock is worth more.
+        assert_eq!(id("Spock"), game.for_result(id("Rock"), GameResult::Win));
+        assert_eq!(6 + 5, game.score_guide("Rock Z", Reading::Result).unwrap());
+        for theirs in game.shapes() {
+            let wins = game
+                .shapes()
+                .filter(|&s| game.versus(s, theirs) == GameResult::Win);
+            assert_eq!(2, wins.count());
+        }
+    }
+
+    #[test]
+    fn reports_ill_formed_games() {
+        let messages = |rules: &str| {
+            let ParseErrors(errs) = rules.parse::<Game>().unwrap_err();
+            errs.into_iter()
+                .map(|e| (e.line, e.message))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                (Some(3), "There's already a shape by that name".to_owned()),
+                (Some(4), "There's no shape by that name".to_owned()),
+                (Some(6), "Paper already beats Rock".to_owned()),
+            ],
+            messages(
+                "shape Rock 1\nshape Paper 2\nshape Rock 3\nbeats Rock Lizard\n\
+                 beats Paper Rock\nbeats Rock Paper"
+            )
+        );
+        assert_eq!(
+            vec![(Some(1), "A game needs at least three shapes".to_owned())],
+            messages("shape Rock 1")
+        );
+        // With two shapes, one of them always loses, so it can't win for you.
+        assert_eq!(
+            vec![(Some(1), "A game needs at least three shapes".to_owned())],
+            messages("shape Rock 1\nshape Paper 2\nbeats Paper Rock")
+        );
+        assert_eq!(
+            vec![(Some(3), "Neither Paper nor Scissors beats the other".to_owned())],
+            messages("shape Rock 1\nshape Paper 2\nshape Scissors 3\nbeats Paper Rock\nbeats Rock Scissors")
+        );
+        assert_eq!(
+            vec![
+                (Some(2), "A shape can't beat itself".to_owned()),
+                (Some(3), "Results are win, draw or loss".to_owned()),
+                (Some(4), "Values are whole numbers".to_owned()),
+            ],
+            messages("shape A 1\nbeats A A\nscore tie 1\nshape B x")
+        );
+        // Rock beats both the others, so there's no way to beat it.
+        let unbeatable = "shape R 1 A X\nshape P 2 B Y\nshape S 3 C Z\nbeats R P S\nbeats P S";
+        assert_eq!(
+            vec![
+                (Some(1), "No shape beats R".to_owned()),
+                (Some(3), "S doesn't beat any shape".to_owned()),
+            ],
+            messages(unbeatable)
+        );
+        assert_eq!(
+            vec![
+                (Some(1), "A doesn't beat any shape".to_owned()),
+                (Some(2), "No shape beats B".to_owned()),
+            ],
+            messages("shape A 1\nshape B 2\nshape C 3\nbeats B A C\nbeats C A")
+        );
+    }
+}
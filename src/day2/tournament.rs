@@ -0,0 +1,516 @@
+//! Round-robin rock-paper-scissors tournaments between strategies.
+//!
+//! Every player plays every other player once, a match being a fixed number of rounds scored
+//! with [`Shape::score`], and the players are ranked by their total score.
+
+use color_eyre::{eyre::eyre, Result};
+use std::fmt::Display;
+
+use super::{GameResult, Reading, Shape, StrategyGuide};
+use crate::gen::Rng;
+use crate::json::Json;
+use crate::trace;
+
+/// How a player picks their throws.
+pub trait Strategy {
+    fn name(&self) -> String;
+
+    /// Gets ready for a new match.
+    fn start(&mut self) {}
+
+    /// The next throw.
+    fn throw(&mut self) -> Shape;
+
+    /// Sees what the opponent threw this round.
+    fn saw(&mut self, _theirs: Shape) {}
+}
+
+/// Always throws the same shape.
+pub struct Fixed(pub Shape);
+
+impl Strategy for Fixed {
+    fn name(&self) -> String {
+        format!("Always {:?}", self.0)
+    }
+
+    fn throw(&mut self) -> Shape {
+        self.0
+    }
+}
+
+/// Throws the same shapes over and over, in order.
+pub struct Cycle {
+    shapes: Vec<Shape>,
+    next: usize,
+}
+
+impl Cycle {
+    /// Cycles through `shapes`, which must not be empty.
+    pub fn new(shapes: Vec<Shape>) -> Self {
+        assert!(!shapes.is_empty(), "Nothing to cycle through");
+        Self { shapes, next: 0 }
+    }
+}
+
+impl Strategy for Cycle {
+    fn name(&self) -> String {
+        let shapes: Vec<_> = self.shapes.iter().map(|s| format!("{s:?}")).collect();
+        format!("Cycle {}", shapes.join("-"))
+    }
+
+    fn start(&mut self) {
+        self.next = 0;
+    }
+
+    fn throw(&mut self) -> Shape {
+        let shape = self.shapes[self.next % self.shapes.len()];
+        self.next += 1;
+        shape
+    }
+}
+
+/// Throws at random, the same way every match for the same seed.
+pub struct Random {
+    seed: u64,
+    rng: Rng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Strategy for Random {
+    fn name(&self) -> String {
+        format!("Random (seed {})", self.seed)
+    }
+
+    fn start(&mut self) {
+        self.rng = Rng::new(self.seed);
+    }
+
+    fn throw(&mut self) -> Shape {
+        *self.rng.pick(&Shape::ALL)
+    }
+}
+
+/// Throws whatever beats the opponent's most common throw so far.
+#[derive(Default)]
+pub struct Counter {
+    seen: [usize; 3],
+}
+
+impl Strategy for Counter {
+    fn name(&self) -> String {
+        "Counter".to_owned()
+    }
+
+    fn start(&mut self) {
+        self.seen = [0; 3];
+    }
+
+    fn throw(&mut self) -> Shape {
+        // The first most common, so Paper to beat Rock before anything has been seen.
+        let (idx, _) = self
+            .seen
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, count)| count)
+            .unwrap();
+        Shape::ALL[idx].for_result(GameResult::Win)
+    }
+
+    fn saw(&mut self, theirs: Shape) {
+        let idx = Shape::ALL.iter().position(|&s| s == theirs).unwrap();
+        self.seen[idx] += 1;
+    }
+}
+
+/// Plays a strategy guide's rounds in order, starting again from the top when it runs out.
+pub struct FollowGuide {
+    name: String,
+    throws: Cycle,
+}
+
+impl FollowGuide {
+    /// Plays our throws from `guide`, reading it `reading`'s way.
+    pub fn new(guide: &StrategyGuide, reading: Reading) -> Result<Self> {
+        let throws = guide.rounds.iter().map(|r| r.my_shape(reading)).collect();
+        let name = match reading {
+            Reading::Shape => "Guide (shapes)",
+            Reading::Result => "Guide",
+        };
+        Self::with(name, throws)
+    }
+
+    /// Plays the opponent's throws from `guide`, as the elf expects them to.
+    pub fn opponent(guide: &StrategyGuide) -> Result<Self> {
+        Self::with(
+            "Guide's opponent",
+            guide.rounds.iter().map(|r| r.theirs).collect(),
+        )
+    }
+
+    fn with(name: &str, throws: Vec<Shape>) -> Result<Self> {
+        if throws.is_empty() {
+            return Err(eyre!("The strategy guide has no rounds to follow"));
+        }
+        Ok(Self {
+            name: name.to_owned(),
+            throws: Cycle::new(throws),
+        })
+    }
+}
+
+impl Strategy for FollowGuide {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn start(&mut self) {
+        self.throws.start();
+    }
+
+    fn throw(&mut self) -> Shape {
+        self.throws.throw()
+    }
+}
+
+fn shape_letter(c: char) -> Option<Shape> {
+    match c {
+        'r' => Some(Shape::Rock),
+        'p' => Some(Shape::Paper),
+        's' => Some(Shape::Scissors),
+        _ => None,
+    }
+}
+
+/// Makes a strategy from a description like `rock`, `cycle:rps`, `random:7`, `counter`, `guide`
+/// or `guide:shapes`, the last two following `guide`.
+pub fn strategy(spec: &str, guide: &StrategyGuide) -> Result<Box<dyn Strategy>> {
+    let (kind, arg) = match spec.split_once(':') {
+        Some((kind, arg)) => (kind, Some(arg)),
+        None => (spec, None),
+    };
+    Ok(match (kind, arg) {
+        ("rock", None) => Box::new(Fixed(Shape::Rock)),
+        ("paper", None) => Box::new(Fixed(Shape::Paper)),
+        ("scissors", None) => Box::new(Fixed(Shape::Scissors)),
+        ("cycle", shapes) => {
+            let shapes = shapes
+                .unwrap_or("rps")
+                .chars()
+                .map(|c| shape_letter(c).ok_or(eyre!("Cycles are of r, p and s, not {c}")))
+                .collect::<Result<Vec<_>>>()?;
+            if shapes.is_empty() {
+                return Err(eyre!("Nothing to cycle through"));
+            }
+            Box::new(Cycle::new(shapes))
+        }
+        ("random", seed) => {
+            let seed = seed
+                .unwrap_or("0")
+                .parse()
+                .map_err(|e| eyre!("Invalid seed in {spec}: {e}"))?;
+            Box::new(Random::new(seed))
+        }
+        ("counter", None) => Box::new(Counter::default()),
+        ("guide", None) => Box::new(FollowGuide::new(guide, Reading::Result)?),
+        ("guide", Some("shapes")) => Box::new(FollowGuide::new(guide, Reading::Shape)?),
+        _ => {
+            return Err(eyre!(
+                "Unknown strategy {spec}, expected rock, paper, scissors, cycle[:SHAPES], \
+                 random[:SEED], counter, guide or guide:shapes"
+            ))
+        }
+    })
+}
+
+/// The players when none are chosen.
+pub const DEFAULT_PLAYERS: &str = "rock,cycle,random,counter,guide";
+
+/// How one player did against another, from that player's side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Record {
+    pub score: u64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Record {
+    fn add(&mut self, other: &Record) {
+        self.score += other.score;
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+}
+
+/// A player's place in the tournament, counting matches won rather than rounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    /// Index of the player in [`Tournament::names`].
+    pub player: usize,
+    pub matches: Record,
+}
+
+/// The outcome of a round-robin tournament.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tournament {
+    pub names: Vec<String>,
+    pub rounds: usize,
+    /// `head_to_head[a][b]` is how player `a`'s rounds against `b` went, or `None` when `a == b`.
+    pub head_to_head: Vec<Vec<Option<Record>>>,
+}
+
+/// Plays one match between `a` and `b`, returning each one's record.
+fn play(a: &mut dyn Strategy, b: &mut dyn Strategy, rounds: usize) -> (Record, Record) {
+    a.start();
+    b.start();
+    let (mut ours, mut theirs) = (Record::default(), Record::default());
+    for _ in 0..rounds {
+        let (x, y) = (a.throw(), b.throw());
+        a.saw(y);
+        b.saw(x);
+        trace!("{} threw {x:?}, {} threw {y:?}", a.name(), b.name());
+        ours.score += x.score(&y);
+        theirs.score += y.score(&x);
+        match x.versus(&y) {
+            GameResult::Win => (ours.wins += 1, theirs.losses += 1),
+            GameResult::Draw => (ours.draws += 1, theirs.draws += 1),
+            GameResult::Loss => (ours.losses += 1, theirs.wins += 1),
+        };
+    }
+    (ours, theirs)
+}
+
+/// Plays every player against every other for `rounds` rounds.
+pub fn round_robin(players: &mut [Box<dyn Strategy>], rounds: usize) -> Tournament {
+    let count = players.len();
+    let mut head_to_head = vec![vec![None; count]; count];
+    for (a, b) in (1..count).flat_map(|b| (0..b).map(move |a| (a, b))) {
+        let (before, rest) = players.split_at_mut(b);
+        let (ours, theirs) = play(before[a].as_mut(), rest[0].as_mut(), rounds);
+        head_to_head[a][b] = Some(ours);
+        head_to_head[b][a] = Some(theirs);
+    }
+    Tournament {
+        names: players.iter().map(|p| p.name()).collect(),
+        rounds,
+        head_to_head,
+    }
+}
+
+impl Tournament {
+    /// Every player's record over all their rounds.
+    pub fn totals(&self) -> Vec<Record> {
+        self.head_to_head
+            .iter()
+            .map(|row| {
+                let mut total = Record::default();
+                row.iter().flatten().for_each(|r| total.add(r));
+                total
+            })
+            .collect()
+    }
+
+    /// The players from best to worst by total score, then by matches won.
+    pub fn standings(&self) -> Vec<Standing> {
+        let totals = self.totals();
+        let mut standings: Vec<_> = self
+            .head_to_head
+            .iter()
+            .enumerate()
+            .map(|(player, row)| {
+                let mut matches = Record {
+                    score: totals[player].score,
+                    ..Record::default()
+                };
+                for record in row.iter().flatten() {
+                    match record.wins.cmp(&record.losses) {
+                        std::cmp::Ordering::Greater => matches.wins += 1,
+                        std::cmp::Ordering::Equal => matches.draws += 1,
+                        std::cmp::Ordering::Less => matches.losses += 1,
+                    }
+                }
+                Standing { player, matches }
+            })
+            .collect();
+        standings.sort_by_key(|s| std::cmp::Reverse((s.matches.score, s.matches.wins)));
+        standings
+    }
+}
+
+impl Display for Tournament {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.names.iter().map(String::len).max().unwrap_or(0);
+        writeln!(
+            f,
+            "{:<4} {:<width$} {:>7} {:>4} {:>5} {:>4}",
+            "Rank", "Player", "Score", "Won", "Drawn", "Lost"
+        )?;
+        for (rank, standing) in self.standings().iter().enumerate() {
+            let m = &standing.matches;
+            writeln!(
+                f,
+                "{:<4} {:<width$} {:>7} {:>4} {:>5} {:>4}",
+                rank + 1,
+                self.names[standing.player],
+                m.score,
+                m.wins,
+                m.draws,
+                m.losses
+            )?;
+        }
+
+        // Each cell is the row player's rounds won, drawn and lost against the column player.
+        writeln!(f, "\nRounds won-drawn-lost against each player")?;
+        let cells: Vec<Vec<String>> = self
+            .head_to_head
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|r| {
+                        r.map_or("-".to_owned(), |r| {
+                            format!("{}-{}-{}", r.wins, r.draws, r.losses)
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        let cell = cells.iter().flatten().map(String::len).max().unwrap_or(1);
+        write!(f, "{:<4} {:<width$}", "", "")?;
+        for idx in 0..self.names.len() {
+            write!(f, " {:>cell$}", idx + 1)?;
+        }
+        for (idx, row) in cells.iter().enumerate() {
+            write!(f, "\n{:<4} {:<width$}", idx + 1, self.names[idx])?;
+            for cell_text in row {
+                write!(f, " {cell_text:>cell$}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&Record> for Json {
+    fn from(record: &Record) -> Self {
+        Json::object()
+            .with("score", record.score)
+            .with("wins", record.wins)
+            .with("draws", record.draws)
+            .with("losses", record.losses)
+    }
+}
+
+impl From<&Tournament> for Json {
+    fn from(tournament: &Tournament) -> Self {
+        let standings: Vec<_> = tournament
+            .standings()
+            .iter()
+            .map(|s| {
+                Json::object()
+                    .with("player", tournament.names[s.player].as_str())
+                    .with("matches", &s.matches)
+            })
+            .collect();
+        let head_to_head: Vec<_> = tournament
+            .head_to_head
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|r| r.as_ref().map(Json::from))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Json::object()
+            .with("rounds", tournament.rounds)
+            .with("players", tournament.names.clone())
+            .with("standings", standings)
+            .with("head_to_head", head_to_head)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day2::{parse_guide, Day2};
+    use crate::gen;
+
+    fn players(specs: &str) -> Vec<Box<dyn Strategy>> {
+        let guide = parse_guide("A Y\nB X\nC Z").unwrap();
+        specs
+            .split(',')
+            .map(|s| strategy(s, &guide).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn counter_beats_a_fixed_throw() {
+        let t = round_robin(&mut players("scissors,counter"), 10);
+        let counter = t.head_to_head[1][0].unwrap();
+        // Paper loses the first round, then it's rock every time.
+        assert_eq!((9, 1, 0), (counter.wins, counter.losses, counter.draws));
+        assert_eq!(9 * (6 + 1) + 2, counter.score);
+        assert_eq!("Counter", t.names[t.standings()[0].player]);
+    }
+
+    #[test]
+    fn records_agree_from_both_sides() {
+        let t = round_robin(&mut players(DEFAULT_PLAYERS), 25);
+        for a in 0..t.names.len() {
+            assert_eq!(None, t.head_to_head[a][a]);
+            for b in (0..t.names.len()).filter(|&b| b != a) {
+                let (ours, theirs) = (t.head_to_head[a][b].unwrap(), t.head_to_head[b][a].unwrap());
+                assert_eq!((ours.wins, ours.draws), (theirs.losses, theirs.draws));
+                assert_eq!(25, ours.wins + ours.draws + ours.losses);
+            }
+        }
+        let standings = t.standings();
+        assert!(standings
+            .windows(2)
+            .all(|w| w[0].matches.score >= w[1].matches.score));
+        // Playing again gives the same result, random players included.
+        assert_eq!(t, round_robin(&mut players(DEFAULT_PLAYERS), 25));
+    }
+
+    #[test]
+    fn following_the_guide_scores_part_two() {
+        gen::check_generated::<Day2>(|_, guide| {
+            let mut players: Vec<Box<dyn Strategy>> = vec![
+                Box::new(FollowGuide::new(guide, Reading::Result).unwrap()),
+                Box::new(FollowGuide::opponent(guide).unwrap()),
+            ];
+            let t = round_robin(&mut players, guide.rounds.len());
+            assert_eq!(guide.score(Reading::Result), t.totals()[0].score);
+        });
+    }
+
+    #[test]
+    fn reads_strategies() {
+        let guide = parse_guide("A Y").unwrap();
+        let names: Vec<_> = ["paper", "cycle:rrs", "random:7", "guide:shapes"]
+            .iter()
+            .map(|s| strategy(s, &guide).unwrap().name())
+            .collect();
+        assert_eq!(
+            vec![
+                "Always Paper",
+                "Cycle Rock-Rock-Scissors",
+                "Random (seed 7)",
+                "Guide (shapes)"
+            ],
+            names
+        );
+        for bad in ["rock:1", "cycle:", "cycle:x", "random:x", "lizard"] {
+            assert!(strategy(bad, &guide).is_err(), "{bad}");
+        }
+        assert!(strategy("guide", &parse_guide("").unwrap()).is_err());
+    }
+}
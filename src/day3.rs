@@ -0,0 +1,527 @@
+use color_eyre::{eyre::eyre, Result};
+use std::fmt::Display;
+use std::ops::{BitAnd, BitOr};
+use std::str::FromStr;
+
+use crate::gen::{Generator, Rng};
+use crate::json::Json;
+use crate::parse::{self, ParseError};
+use crate::{trace, Solution};
+
+pub mod repack;
+
+/// The priority of an item, from 1 for `a` to 52 for `Z`, or `None` if it isn't an item.
+pub fn priority(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Some(c as u32 - 'A' as u32 + 27),
+        _ => None,
+    }
+}
+
+/// The item with priority `priority`, the opposite of [`priority`].
+pub fn item(priority: u32) -> Option<char> {
+    match priority {
+        1..=26 => char::from_u32('a' as u32 + priority - 1),
+        27..=52 => char::from_u32('A' as u32 + priority - 27),
+        _ => None,
+    }
+}
+
+/// A set of item types, as one bit per priority.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Items(u64);
+
+impl Items {
+    /// Every item type there is.
+    pub const ALL: Items = Items(((1 << 52) - 1) << 1);
+
+    /// Adds `item`, returning false if it isn't one.
+    pub fn insert(&mut self, item: char) -> bool {
+        match priority(item) {
+            Some(p) => {
+                self.0 |= 1 << p;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, item: char) -> bool {
+        priority(item).is_some_and(|p| self.0 & (1 << p) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The items, from the lowest priority to the highest.
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        (1..=52).filter_map(item).filter(|&c| self.contains(c))
+    }
+
+    /// The item, if there's exactly one.
+    pub fn single(&self) -> Option<char> {
+        match self.len() {
+            1 => item(self.0.trailing_zeros()),
+            _ => None,
+        }
+    }
+
+    /// The total priority of the items.
+    pub fn priority(&self) -> u32 {
+        self.iter().filter_map(priority).sum()
+    }
+}
+
+impl FromIterator<char> for Items {
+    /// Collects the items, skipping anything that isn't one.
+    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
+        let mut items = Items::default();
+        for c in iter {
+            items.insert(c);
+        }
+        items
+    }
+}
+
+impl BitAnd for Items {
+    type Output = Items;
+    fn bitand(self, rhs: Self) -> Self {
+        Items(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for Items {
+    type Output = Items;
+    fn bitor(self, rhs: Self) -> Self {
+        Items(self.0 | rhs.0)
+    }
+}
+
+impl Display for Items {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.iter().try_for_each(|c| write!(f, "{c}"))
+    }
+}
+
+/// A rucksack, whose items are split evenly between two compartments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rucksack {
+    /// The items as listed, the first half in the first compartment.
+    pub contents: String,
+    pub first: Items,
+    pub second: Items,
+}
+
+impl Rucksack {
+    /// The items in each compartment, as listed.
+    pub fn compartments(&self) -> (&str, &str) {
+        self.contents.split_at(self.contents.len() / 2)
+    }
+
+    /// Every type of item in the rucksack.
+    pub fn items(&self) -> Items {
+        self.first | self.second
+    }
+
+    /// The types of item in both compartments.
+    pub fn shared(&self) -> Items {
+        self.first & self.second
+    }
+
+    /// The one type of item packed in both compartments, which the puzzle says there always is.
+    pub fn misplaced(&self) -> Result<char> {
+        let shared = self.shared();
+        shared.single().ok_or_else(|| match shared.len() {
+            0 => eyre!("{} has no item in both compartments", self.contents),
+            _ => eyre!("{} has {shared} in both compartments", self.contents),
+        })
+    }
+}
+
+impl FromStr for Rucksack {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((idx, c)) = s.char_indices().find(|&(_, c)| priority(c).is_none()) {
+            return Err(ParseError::at(
+                "Items are letters from a to z or A to Z",
+                s,
+                &s[idx..idx + c.len_utf8()],
+            ));
+        }
+        if !s.len().is_multiple_of(2) {
+            return Err(ParseError::new(
+                format!(
+                    "Compartments hold the same number of items, but there are {}",
+                    s.len()
+                ),
+                s,
+            ));
+        }
+        let (first, second) = s.split_at(s.len() / 2);
+        Ok(Rucksack {
+            contents: s.to_owned(),
+            first: first.chars().collect(),
+            second: second.chars().collect(),
+        })
+    }
+}
+
+/// How rucksacks are grouped when looking for badges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Windows {
+    /// Each rucksack is in one group, with the ones next to it.
+    Fixed,
+    /// Every run of rucksacks in a row is a group, so they overlap.
+    Sliding,
+}
+
+/// How many rucksacks make a group, and how they're picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grouping {
+    pub size: usize,
+    pub windows: Windows,
+}
+
+impl Default for Grouping {
+    /// Groups of three elves, as in the puzzle.
+    fn default() -> Self {
+        Self {
+            size: 3,
+            windows: Windows::Fixed,
+        }
+    }
+}
+
+/// A group of rucksacks in a row, and the items all of them have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Group {
+    /// Index of the group's first rucksack.
+    pub start: usize,
+    pub size: usize,
+    pub common: Items,
+}
+
+impl Group {
+    /// The group's badge, if they have exactly one item in common.
+    pub fn badge(&self) -> Option<char> {
+        self.common.single()
+    }
+}
+
+impl Display for Group {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (first, last) = (self.start + 1, self.start + self.size);
+        match (self.badge(), self.common.len()) {
+            (Some(badge), _) => write!(f, "Rucksacks {first}-{last} share {badge}"),
+            (None, 0) => write!(f, "Rucksacks {first}-{last} have nothing in common"),
+            (None, n) => write!(
+                f,
+                "Rucksacks {first}-{last} share {n} items: {}",
+                self.common
+            ),
+        }
+    }
+}
+
+/// Splits the rucksacks into groups, finding what each has in common.
+pub fn groups(rucksacks: &[Rucksack], grouping: Grouping) -> Result<Vec<Group>> {
+    let size = grouping.size;
+    if size == 0 {
+        return Err(eyre!("Groups need at least one rucksack"));
+    }
+    let starts = match grouping.windows {
+        Windows::Fixed if !rucksacks.len().is_multiple_of(size) => {
+            return Err(eyre!(
+                "Rucksacks come in groups of {size}, but there are {}",
+                rucksacks.len()
+            ))
+        }
+        Windows::Fixed => (0..rucksacks.len()).step_by(size),
+        Windows::Sliding => (0..(rucksacks.len() + 1).saturating_sub(size)).step_by(1),
+    };
+    Ok(starts
+        .map(|start| {
+            let group = &rucksacks[start..start + size];
+            let common = group
+                .iter()
+                .fold(Items::ALL, |common, r| common & r.items());
+            Group {
+                start,
+                size,
+                common,
+            }
+        })
+        .collect())
+}
+
+/// Finds the item each group of three elves has in common, in order.
+pub fn badges(rucksacks: &[Rucksack]) -> Result<Vec<char>> {
+    groups(rucksacks, Grouping::default())?
+        .into_iter()
+        .map(|group| {
+            let badge = group
+                .badge()
+                .ok_or_else(|| eyre!("{group}, not one badge"))?;
+            trace!("{group} (pri {})", group.common.priority());
+            Ok(badge)
+        })
+        .collect()
+}
+
+impl From<&Group> for Json {
+    fn from(group: &Group) -> Self {
+        Json::object()
+            .with("first", group.start + 1)
+            .with("last", group.start + group.size)
+            .with("common", group.common.to_string())
+            .with("badge", group.badge())
+            .with("priority", group.badge().and_then(priority))
+    }
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    /// The rucksacks, one per line.
+    type Input = Vec<Rucksack>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::parse_lines(input, str::parse)?)
+    }
+
+    fn part1(rucksacks: &Self::Input) -> Result<u32> {
+        rucksacks
+            .iter()
+            .map(|r| r.misplaced().map(|c| priority(c).unwrap()))
+            .sum()
+    }
+
+    fn part2(rucksacks: &Self::Input) -> Result<u32> {
+        Ok(badges(rucksacks)?.into_iter().filter_map(priority).sum())
+    }
+
+    fn details(rucksacks: &Self::Input) -> Result<Json> {
+        let misplaced: Vec<_> = rucksacks
+            .iter()
+            .map(|r| {
+                let item = r.misplaced();
+                Json::object()
+                    .with("item", item.as_ref().ok().copied())
+                    .with("priority", item.ok().and_then(priority))
+            })
+            .collect();
+        let groups: Vec<_> = groups(rucksacks, Grouping::default())?
+            .iter()
+            .map(Json::from)
+            .collect();
+        Ok(Json::object()
+            .with("misplaced", misplaced)
+            .with("groups", groups))
+    }
+}
+
+impl Generator for Day3 {
+    /// `size` groups of three elves.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut s = String::new();
+        for _ in 0..size.max(1) {
+            let mut items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+            rng.shuffle(&mut items);
+            let badge = items.pop().unwrap();
+            // Each elf packs from their own share of the other items, so only the badge is common
+            // to the whole group.
+            for pool in items.chunks(items.len() / 3).take(3) {
+                let mut pool = pool.to_vec();
+                pool.push(badge);
+                rng.shuffle(&mut pool);
+                let shared = pool.pop().unwrap();
+                // The rest go in one compartment or the other, the badge with them if not shared.
+                let (left, right) = pool.split_at(pool.len() / 2);
+                let half = rng.between(2, 12) as usize;
+                let mut compartments = [vec![shared], vec![shared]];
+                for (compartment, only) in compartments.iter_mut().zip([left, right]) {
+                    if only.contains(&badge) {
+                        compartment.push(badge);
+                    }
+                    while compartment.len() < half {
+                        compartment.push(*rng.pick(only));
+                    }
+                }
+                for compartment in &mut compartments {
+                    rng.shuffle(compartment);
+                    s.extend(compartment.iter());
+                }
+                s.push('\n');
+            }
+        }
+        s
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        let common = |first: &str, others: &[&str]| {
+            first
+                .chars()
+                .find(|&c| others.iter().all(|o| o.contains(c)))
+                .and_then(priority)
+                .unwrap()
+        };
+        let part1: u32 = input
+            .lines()
+            .map(|l| {
+                let (left, right) = l.split_at(l.len() / 2);
+                common(left, &[right])
+            })
+            .sum();
+        let lines: Vec<_> = input.lines().collect();
+        let part2: u32 = lines
+            .chunks(3)
+            .map(|group| common(group[0], &group[1..]))
+            .sum();
+        [Some(part1.to_string()), Some(part2.to_string())]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day3>(Part::Both);
+    }
+
+    #[test]
+    fn items_are_a_set_of_priorities() {
+        let items: Items = "vJrwpWtwJgWr".chars().collect();
+        assert_eq!("gprtvwJW", items.to_string());
+        assert_eq!(8, items.len());
+        assert!(items.contains('J') && !items.contains('j') && !items.contains('1'));
+        assert_eq!(
+            Some('p'),
+            (items & "hcsFMMfFFhFp".chars().collect()).single()
+        );
+        assert_eq!(Items::ALL, ('a'..='z').chain('A'..='Z').collect());
+        for p in 1..=52 {
+            assert_eq!(Some(p), item(p).and_then(priority));
+        }
+        assert_eq!((None, None), (priority('!'), item(53)));
+    }
+
+    #[test]
+    fn rucksacks_are_validated() {
+        let err = |s: &str| s.parse::<Rucksack>().unwrap_err().message;
+        assert_eq!("Items are letters from a to z or A to Z", err("ab1c"));
+        assert_eq!(
+            "Compartments hold the same number of items, but there are 3",
+            err("abc")
+        );
+        let rucksack: Rucksack = "vJrwpWtwJgWrhcsFMMfFFhFp".parse().unwrap();
+        assert_eq!(("vJrwpWtwJgWr", "hcsFMMfFFhFp"), rucksack.compartments());
+        assert_eq!('p', rucksack.misplaced().unwrap());
+        assert!("abab".parse::<Rucksack>().unwrap().misplaced().is_err());
+        assert!("abcd".parse::<Rucksack>().unwrap().misplaced().is_err());
+    }
+
+    fn example() -> Vec<Rucksack> {
+        Day3::parse(include_str!("../inputs/examples/day3.txt")).unwrap()
+    }
+
+    #[test]
+    fn groups_of_any_size() {
+        let rucksacks = example();
+        let badges = |grouping| -> Vec<_> {
+            groups(&rucksacks, grouping)
+                .unwrap()
+                .iter()
+                .map(|g| g.badge())
+                .collect()
+        };
+        assert_eq!(vec![Some('r'), Some('Z')], badges(Grouping::default()));
+        let sliding = Grouping {
+            size: 3,
+            windows: Windows::Sliding,
+        };
+        assert_eq!(
+            vec![Some('r'), Some('q'), Some('T'), Some('Z')],
+            badges(sliding)
+        );
+        let pairs = Grouping {
+            size: 2,
+            windows: Windows::Fixed,
+        };
+        assert_eq!(3, badges(pairs).len());
+        let everyone = Grouping {
+            size: 6,
+            windows: Windows::Sliding,
+        };
+        assert_eq!(vec![None], badges(everyone));
+        let too_many = Grouping {
+            size: 7,
+            windows: Windows::Sliding,
+        };
+        assert!(badges(too_many).is_empty());
+        for size in [0, 4] {
+            let grouping = Grouping {
+                size,
+                windows: Windows::Fixed,
+            };
+            assert!(groups(&rucksacks, grouping).is_err());
+        }
+    }
+
+    #[test]
+    fn reports_groups_without_one_badge() {
+        let rucksacks = example();
+        let grouping = Grouping {
+            size: 2,
+            windows: Windows::Sliding,
+        };
+        let report: Vec<_> = groups(&rucksacks, grouping)
+            .unwrap()
+            .iter()
+            .map(Group::to_string)
+            .collect();
+        assert_eq!("Rucksacks 1-2 share 5 items: frsFM", report[0]);
+        let strangers = Day3::parse("abcd\nefgh").unwrap();
+        let fixed = Grouping {
+            size: 2,
+            windows: Windows::Fixed,
+        };
+        assert_eq!(
+            "Rucksacks 1-2 have nothing in common",
+            groups(&strangers, fixed).unwrap()[0].to_string()
+        );
+        assert!(badges(&Day3::parse("ab\nab\nab").unwrap()).is_err());
+        let one = Grouping {
+            size: 1,
+            windows: Windows::Fixed,
+        };
+        assert!(groups(&rucksacks, one)
+            .unwrap()
+            .iter()
+            .all(|g| g.badge().is_none()));
+    }
+
+    #[test]
+    fn badges_are_in_every_rucksack_of_the_group() {
+        gen::check_generated::<Day3>(|_, rucksacks| {
+            let badges = badges(rucksacks).unwrap();
+            assert_eq!(rucksacks.len() / 3, badges.len());
+            for (group, badge) in rucksacks.chunks(3).zip(badges) {
+                assert!(group.iter().all(|r| r.items().contains(badge)));
+            }
+        });
+    }
+}
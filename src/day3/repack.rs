@@ -0,0 +1,170 @@
+//! Repacking rucksacks so no type of item is in both compartments.
+//!
+//! Every type of item in both compartments has to end up in just one of them, so the fewest
+//! moves is to take all of it out of whichever compartment has fewer. Compartments may not hold
+//! the same number of items afterwards.
+
+use std::fmt::Display;
+
+use super::{priority, Rucksack};
+use crate::json::Json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compartment {
+    First,
+    Second,
+}
+
+/// Moving every one of an item out of one compartment and into the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemMove {
+    pub item: char,
+    pub count: usize,
+    pub to: Compartment,
+}
+
+/// How to repack a rucksack, and what it looks like afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repack {
+    pub moves: Vec<ItemMove>,
+    pub first: String,
+    pub second: String,
+    /// The priority of the items that were in both compartments, which no longer are.
+    pub saved: u32,
+}
+
+impl Repack {
+    /// How many items move.
+    pub fn items_moved(&self) -> usize {
+        self.moves.iter().map(|m| m.count).sum()
+    }
+}
+
+/// Works out the fewest moves to repack `rucksack`.
+pub fn repack(rucksack: &Rucksack) -> Repack {
+    let (first, second) = rucksack.compartments();
+    let (mut first, mut second) = (first.to_owned(), second.to_owned());
+    let mut moves = Vec::new();
+    for item in rucksack.shared().iter() {
+        let count = |s: &str| s.chars().filter(|&c| c == item).count();
+        let (in_first, in_second) = (count(&first), count(&second));
+        // Ties move out of the fuller compartment, to keep them closer in size.
+        let to = match in_first.cmp(&in_second) {
+            std::cmp::Ordering::Less => Compartment::Second,
+            std::cmp::Ordering::Greater => Compartment::First,
+            std::cmp::Ordering::Equal if first.len() > second.len() => Compartment::Second,
+            std::cmp::Ordering::Equal => Compartment::First,
+        };
+        let (from, into, count) = match to {
+            Compartment::First => (&mut second, &mut first, in_second),
+            Compartment::Second => (&mut first, &mut second, in_first),
+        };
+        from.retain(|c| c != item);
+        into.extend(std::iter::repeat_n(item, count));
+        moves.push(ItemMove { item, count, to });
+    }
+    Repack {
+        moves,
+        first,
+        second,
+        saved: rucksack.shared().priority(),
+    }
+}
+
+impl Display for ItemMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let to = match self.to {
+            Compartment::First => "first",
+            Compartment::Second => "second",
+        };
+        match self.count {
+            1 => write!(f, "Move the {} to the {to} compartment", self.item),
+            n => write!(f, "Move all {n} {}s to the {to} compartment", self.item),
+        }
+    }
+}
+
+impl From<&Repack> for Json {
+    fn from(repack: &Repack) -> Self {
+        let moves: Vec<_> = repack
+            .moves
+            .iter()
+            .map(|m| {
+                Json::object()
+                    .with("item", m.item)
+                    .with("priority", priority(m.item))
+                    .with("count", m.count)
+                    .with("to", format!("{:?}", m.to).to_lowercase())
+            })
+            .collect();
+        Json::object()
+            .with("moves", moves)
+            .with("first", repack.first.as_str())
+            .with("second", repack.second.as_str())
+            .with("saved", repack.saved)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day3::{Day3, Items};
+    use crate::gen;
+
+    #[test]
+    fn moves_the_fewest_items() {
+        let rucksack: Rucksack = "aabBBcabbBdc".parse().unwrap();
+        let plan = repack(&rucksack);
+        // Each item goes where there are more of it already, and the tied c to the first, as
+        // neither compartment is fuller by then.
+        assert_eq!(
+            vec![
+                ItemMove {
+                    item: 'a',
+                    count: 1,
+                    to: Compartment::First
+                },
+                ItemMove {
+                    item: 'b',
+                    count: 1,
+                    to: Compartment::Second
+                },
+                ItemMove {
+                    item: 'c',
+                    count: 1,
+                    to: Compartment::First
+                },
+                ItemMove {
+                    item: 'B',
+                    count: 1,
+                    to: Compartment::First
+                },
+            ],
+            plan.moves
+        );
+        assert_eq!(
+            ("aaBBcacB", "bbdb"),
+            (plan.first.as_str(), plan.second.as_str())
+        );
+        assert_eq!(1 + 2 + 3 + 28, plan.saved);
+        assert_eq!(4, plan.items_moved());
+    }
+
+    #[test]
+    fn repacked_compartments_share_nothing() {
+        gen::check_generated::<Day3>(|_, rucksacks| {
+            for rucksack in rucksacks {
+                let plan = repack(rucksack);
+                let (first, second): (Items, Items) =
+                    (plan.first.chars().collect(), plan.second.chars().collect());
+                assert!((first & second).is_empty());
+                assert_eq!(rucksack.items(), first | second);
+                assert_eq!(
+                    rucksack.contents.len(),
+                    plan.first.len() + plan.second.len()
+                );
+                assert_eq!(priority(rucksack.misplaced().unwrap()), Some(plan.saved));
+            }
+        });
+    }
+}
@@ -0,0 +1,367 @@
+use color_eyre::Result;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::gen::{Generator, Rng};
+use crate::intervals::{Interval, IntervalSet};
+use crate::json::Json;
+use crate::parse::{self, ParseError};
+use crate::{trace, Solution};
+
+pub mod coverage;
+pub mod roster;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CleaningRange {
+    pub first: u64,
+    pub last: u64,
+}
+
+impl CleaningRange {
+    /// The sections in the range, or `None` if it's reversed and has none.
+    pub fn interval(&self) -> Option<Interval> {
+        Interval::new(self.first, self.last)
+    }
+
+    /// The sections in the range, for working with several ranges at once.
+    pub fn sections(&self) -> IntervalSet {
+        IntervalSet::range(self.first, self.last)
+    }
+
+    /// Whether every section in `other` is in this range.
+    pub fn contains(&self, other: &Self) -> bool {
+        match (self.interval(), other.interval()) {
+            (Some(range), Some(other)) => range.covers(&other),
+            (_, None) => true,
+            (None, Some(_)) => false,
+        }
+    }
+
+    /// Whether the two ranges share any section, which is the same either way round.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        match (self.interval(), other.interval()) {
+            (Some(range), Some(other)) => range.overlaps(&other),
+            _ => false,
+        }
+    }
+
+    /// Whether the range was written last section first, like `7-3`, so it covers nothing.
+    pub fn is_reversed(&self) -> bool {
+        self.first > self.last
+    }
+
+    /// The range with its first and last sections swapped.
+    pub fn swapped(&self) -> Self {
+        Self {
+            first: self.last,
+            last: self.first,
+        }
+    }
+
+    /// Reads a range without checking which way round it is.
+    fn read(s: &str) -> Result<Self, ParseError> {
+        let (first, last) = s
+            .split_once('-')
+            .ok_or_else(|| ParseError::new("Missing a '-' in this range", s))?;
+        if let Some(dash) = last.find('-') {
+            return Err(ParseError::at(
+                "A range is only a first and last section",
+                s,
+                &last[dash..],
+            ));
+        }
+        // Only digits, so that `+3` isn't a section either.
+        let section = |n: &str| -> Result<u64, ParseError> {
+            if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ParseError::new("Sections are whole numbers", n).within(s, n));
+            }
+            parse::number(n, "Sections only go up to 18446744073709551615")
+                .map_err(|e| e.within(s, n))
+        };
+        Ok(Self {
+            first: section(first)?,
+            last: section(last)?,
+        })
+    }
+}
+
+/// The two elves' assignments on a line.
+pub type Pair = (CleaningRange, CleaningRange);
+
+/// Every elf's assignment, in order, so elf `n` (counting from 0) is in pair `n / 2`.
+pub fn assignments(pairs: &[Pair]) -> Vec<CleaningRange> {
+    pairs
+        .iter()
+        .flat_map(|&(first, second)| [first, second])
+        .collect()
+}
+
+/// Whether one of the pair's ranges contains the other.
+pub fn either_contains((first, second): &Pair) -> bool {
+    first.contains(second) || second.contains(first)
+}
+
+impl FromStr for CleaningRange {
+    type Err = ParseError;
+    /// Reads a range, which must not be reversed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = Self::read(s)?;
+        match range.is_reversed() {
+            true => Err(reversed(&range, s)),
+            false => Ok(range),
+        }
+    }
+}
+
+fn reversed(range: &CleaningRange, s: &str) -> ParseError {
+    ParseError::new(
+        format!(
+            "This range ends at section {} before it starts at {}",
+            range.last, range.first
+        ),
+        s,
+    )
+}
+
+/// What to do with a range written last section first, like `7-3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reversed {
+    /// Report it as an error.
+    #[default]
+    Reject,
+    /// Turn it round, and say so.
+    Swap,
+}
+
+/// A reversed range that was turned round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Swap {
+    /// The line of the input it was on, counting from 1.
+    pub line: usize,
+    /// The range as it was written.
+    pub written: CleaningRange,
+}
+
+impl Display for Swap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let CleaningRange { first, last } = self.written;
+        write!(
+            f,
+            "Line {}: read {first}-{last} as {last}-{first}",
+            self.line
+        )
+    }
+}
+
+/// Parses the pairs of assignments, one pair per line, doing as `reversed` says with reversed
+/// ranges.
+pub fn parse_pairs_with(s: &str, reversed: Reversed) -> Result<(Vec<Pair>, Vec<Swap>), ParseError> {
+    let mut swaps = Vec::new();
+    let pairs = parse::parse_numbered_lines(s, |line_no, line| {
+        let (first, second) = line
+            .split_once(',')
+            .ok_or_else(|| ParseError::new("Missing a ',' between the two ranges", line))?;
+        if let Some(comma) = second.find(',') {
+            return Err(ParseError::at(
+                "Only two ranges go on a line",
+                line,
+                &second[comma..],
+            ));
+        }
+        let mut range = |text: &str| {
+            let range = CleaningRange::read(text).map_err(|e| e.within(line, text))?;
+            match (range.is_reversed(), reversed) {
+                (false, _) => Ok(range),
+                (true, Reversed::Reject) => Err(self::reversed(&range, text).within(line, text)),
+                (true, Reversed::Swap) => {
+                    swaps.push(Swap {
+                        line: line_no,
+                        written: range,
+                    });
+                    Ok(range.swapped())
+                }
+            }
+        };
+        Ok((range(first)?, range(second)?))
+    })?;
+    Ok((pairs, swaps))
+}
+
+/// Parses the pairs of assignments, one pair per line, none of them reversed.
+pub fn parse_pairs(s: &str) -> Result<Vec<Pair>, ParseError> {
+    Ok(parse_pairs_with(s, Reversed::Reject)?.0)
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<Pair>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_pairs(input)?)
+    }
+
+    fn part1(pairs: &Self::Input) -> Result<usize> {
+        let mut completely_overlaps = 0;
+        for pair in pairs {
+            if either_contains(pair) {
+                trace!("{:?} completely overlaps {:?}", pair.0, pair.1);
+                completely_overlaps += 1;
+            }
+        }
+        Ok(completely_overlaps)
+    }
+
+    fn part2(pairs: &Self::Input) -> Result<usize> {
+        let mut partially_overlaps = 0;
+        for (f_range, s_range) in pairs {
+            if f_range.overlaps(s_range) {
+                trace!("{f_range:?} overlaps {s_range:?}");
+                partially_overlaps += 1;
+            }
+        }
+        Ok(partially_overlaps)
+    }
+
+    fn details(pairs: &Self::Input) -> Result<Json> {
+        let pairs: Vec<_> = pairs
+            .iter()
+            .map(|pair @ (f_range, s_range)| {
+                Json::object()
+                    .with("first", vec![f_range.first, f_range.last])
+                    .with("second", vec![s_range.first, s_range.last])
+                    .with("contains", either_contains(pair))
+                    .with("overlaps", f_range.overlaps(s_range))
+                    .with(
+                        "shared",
+                        f_range
+                            .interval()
+                            .zip(s_range.interval())
+                            .and_then(|(f, s)| f.intersection(&s))
+                            .map(|shared| shared.to_string())
+                            .unwrap_or_default(),
+                    )
+            })
+            .collect();
+        Ok(Json::object().with("pairs", pairs))
+    }
+}
+
+impl Generator for Day4 {
+    /// `size` pairs of elves.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut range = || {
+            let first = rng.between(1, 99);
+            format!("{first}-{}", rng.between(first, 99))
+        };
+        (0..size.max(1))
+            .map(|_| format!("{},{}\n", range(), range()))
+            .collect()
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        let (mut part1, mut part2) = (0, 0);
+        for line in input.lines() {
+            let n: Vec<u64> = line.split([',', '-']).map(|n| n.parse().unwrap()).collect();
+            let sections = |first, last| (first..=last).collect::<Vec<u64>>();
+            let (a, b) = (sections(n[0], n[1]), sections(n[2], n[3]));
+            if a.iter().all(|s| b.contains(s)) || b.iter().all(|s| a.contains(s)) {
+                part1 += 1;
+            }
+            if a.iter().any(|s| b.contains(s)) {
+                part2 += 1;
+            }
+        }
+        [Some(part1.to_string()), Some(part2.to_string())]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day4>(Part::Both);
+    }
+
+    #[test]
+    fn overlapping_doesnt_depend_on_the_order() {
+        // The first range sticks out past both ends of the second.
+        let pairs = parse_pairs("1-10,3-4\n3-4,1-10").unwrap();
+        for (first, second) in &pairs {
+            assert!(first.overlaps(second));
+        }
+        assert_eq!(2, Day4::part2(&pairs).unwrap());
+    }
+
+    #[test]
+    fn rejects_malformed_assignments() {
+        let error = |input: &str| {
+            let err = parse_pairs(input).unwrap_err();
+            (err.message, err.line, err.column)
+        };
+        assert_eq!(
+            (
+                "This range ends at section 3 before it starts at 7".to_owned(),
+                Some(2),
+                5
+            ),
+            error("1-2,3-4\n5-6,7-3")
+        );
+        let missing = "Missing a ',' between the two ranges".to_owned();
+        assert_eq!((missing, Some(1), 1), error("1-2 3-4"));
+        let extra = "Only two ranges go on a line".to_owned();
+        assert_eq!((extra, Some(1), 8), error("1-2,3-4,5-6"));
+        let extra = "A range is only a first and last section".to_owned();
+        assert_eq!((extra, Some(1), 4), error("1-2-3,4-5"));
+        let overflow = "Sections only go up to 18446744073709551615".to_owned();
+        assert_eq!((overflow, Some(1), 7), error("1-2,3-18446744073709551616"));
+        let negative = "Sections are whole numbers".to_owned();
+        assert_eq!((negative, Some(1), 5), error("1-2,+3-4"));
+        assert!("7-3".parse::<CleaningRange>().is_err());
+    }
+
+    #[test]
+    fn normalising_swaps_reversed_ranges() {
+        let (pairs, swaps) = parse_pairs_with("7-3,4-5\n1-1,9-8", Reversed::Swap).unwrap();
+        let ranges: Vec<_> = assignments(&pairs)
+            .iter()
+            .map(|r| (r.first, r.last))
+            .collect();
+        assert_eq!(vec![(3, 7), (4, 5), (1, 1), (8, 9)], ranges);
+        let swaps: Vec<_> = swaps.iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            vec!["Line 1: read 7-3 as 3-7", "Line 2: read 9-8 as 8-9"],
+            swaps
+        );
+        assert_eq!(Ok(1), Day4::part1(&pairs).map_err(|e| e.to_string()));
+    }
+
+    #[test]
+    fn containing_is_overlapping() {
+        gen::check_generated::<Day4>(|_, pairs| {
+            for pair @ (first, second) in pairs {
+                assert_eq!(first.overlaps(second), second.overlaps(first));
+                if first.contains(second) {
+                    assert!(first.overlaps(second), "{first:?} contains {second:?}");
+                }
+                assert!(first.contains(first) && first.overlaps(first));
+                let (f_sections, s_sections) = (first.sections(), second.sections());
+                assert_eq!(first.contains(second), f_sections.is_superset(&s_sections));
+                assert_eq!(first.overlaps(second), f_sections.overlaps(&s_sections));
+                let both = first.sections().union(&second.sections());
+                assert_eq!(
+                    either_contains(pair),
+                    both == first.sections() || both == second.sections()
+                );
+            }
+        });
+    }
+}
@@ -0,0 +1,213 @@
+//! Which sections the whole roster covers, and how many times over.
+//!
+//! A sweep along the sections finds how many elves cover each stretch of them, after sorting
+//! where every assignment starts and ends, so it takes `O(n log n)` for `n` elves however many
+//! sections they're spread over.
+
+use std::fmt::Display;
+
+use super::CleaningRange;
+use crate::intervals::{Interval, IntervalSet};
+use crate::json::Json;
+
+/// Splits the sections the assignments cover into stretches covered by the same number of
+/// elves, in order, with that number. Reversed assignments cover nothing.
+pub fn layers(assignments: &[CleaningRange]) -> Vec<(Interval, usize)> {
+    // Positions are one past the largest section, so assignments can end at `u64::MAX`.
+    let mut events: Vec<(u128, isize)> = assignments
+        .iter()
+        .filter_map(CleaningRange::interval)
+        .flat_map(|i| [(i.start as u128, 1), (i.end as u128 + 1, -1)])
+        .collect();
+    events.sort_unstable();
+    let mut layers = Vec::new();
+    let mut depth = 0;
+    for (idx, &(position, change)) in events.iter().enumerate() {
+        depth += change;
+        match events.get(idx + 1) {
+            Some(&(next, _)) if next > position && depth > 0 => layers.push((
+                Interval {
+                    start: position as u64,
+                    end: (next - 1) as u64,
+                },
+                depth as usize,
+            )),
+            _ => {}
+        }
+    }
+    layers
+}
+
+/// How the roster covers the sections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pub elves: usize,
+    pub covered: IntervalSet,
+    /// The sections covered by more than `crowd` elves.
+    pub crowd: usize,
+    pub crowded: IntervalSet,
+    /// The elves, counting from 0, whose every section is covered by someone else too. Two elves
+    /// with the same assignment are both redundant, though only one of them could be let go.
+    pub redundant: Vec<usize>,
+}
+
+impl Coverage {
+    /// The sections nobody covers between the first and last that somebody does.
+    pub fn gaps(&self) -> IntervalSet {
+        self.covered.gaps()
+    }
+}
+
+/// Works out how `assignments` cover the sections, counting the ones with more than `crowd` elves.
+pub fn coverage(assignments: &[CleaningRange], crowd: usize) -> Coverage {
+    let layers = layers(assignments);
+    let covered_by = |keep: &dyn Fn(usize) -> bool| -> IntervalSet {
+        layers
+            .iter()
+            .filter(|&&(_, depth)| keep(depth))
+            .map(|&(interval, _)| interval)
+            .collect()
+    };
+    // An elf is only needed for the sections nobody else covers.
+    let alone = covered_by(&|depth| depth == 1);
+    let redundant = assignments
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| {
+            a.interval()
+                .is_none_or(|i| alone.overlapping(&i).is_empty())
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+    Coverage {
+        elves: assignments.len(),
+        covered: covered_by(&|_| true),
+        crowd,
+        crowded: covered_by(&|depth| depth > crowd),
+        redundant,
+    }
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let or_none = |set: &IntervalSet| match set.is_empty() {
+            true => "none".to_owned(),
+            false => set.to_string(),
+        };
+        write!(f, "{:<28}{}", "Sections covered", self.covered.coverage())?;
+        if let Some(span) = self.covered.span() {
+            write!(f, " (of {span})")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:<28}{}", "Gaps", or_none(&self.gaps()))?;
+        writeln!(
+            f,
+            "{:<28}{} sections: {}",
+            format!("Covered more than {}", times(self.crowd)),
+            self.crowded.coverage(),
+            or_none(&self.crowded)
+        )?;
+        let redundant: Vec<_> = self.redundant.iter().map(|e| (e + 1).to_string()).collect();
+        write!(
+            f,
+            "{:<28}{} of {}",
+            "Redundant elves",
+            redundant.len(),
+            self.elves
+        )?;
+        if !redundant.is_empty() {
+            write!(f, ": {}", redundant.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+fn times(n: usize) -> String {
+    match n {
+        1 => "once".to_owned(),
+        2 => "twice".to_owned(),
+        n => format!("{n} times"),
+    }
+}
+
+impl From<&Coverage> for Json {
+    fn from(coverage: &Coverage) -> Self {
+        let intervals = |set: &IntervalSet| -> Vec<Json> {
+            set.intervals()
+                .iter()
+                .map(|i| vec![i.start, i.end].into())
+                .collect()
+        };
+        let redundant: Vec<_> = coverage.redundant.iter().map(|e| e + 1).collect();
+        Json::object()
+            .with("elves", coverage.elves)
+            .with("covered", coverage.covered.coverage())
+            .with(
+                "span",
+                coverage.covered.span().map(|s| vec![s.start, s.end]),
+            )
+            .with("gaps", intervals(&coverage.gaps()))
+            .with("crowd", coverage.crowd)
+            .with("crowded", coverage.crowded.coverage())
+            .with("crowded_sections", intervals(&coverage.crowded))
+            .with("redundant", redundant)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day4::{assignments, parse_pairs, Day4};
+    use crate::gen;
+
+    #[test]
+    fn covers_the_example() {
+        let pairs = parse_pairs("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8").unwrap();
+        let coverage = coverage(&assignments(&pairs), 5);
+        assert_eq!("2-9", coverage.covered.to_string());
+        assert!(coverage.gaps().is_empty());
+        // Sections 4 to 7 each have 6 to 8 elves on them.
+        assert_eq!("4-7", coverage.crowded.to_string());
+        // Only the elf on 7-9 covers section 9, and everyone else has help.
+        let needed: Vec<_> = (0..12)
+            .filter(|e| !coverage.redundant.contains(e))
+            .collect();
+        assert_eq!(vec![5], needed);
+        let apart = parse_pairs("1-2,5-6\n5-5,9-9").unwrap();
+        let coverage = super::coverage(&assignments(&apart), 1);
+        assert_eq!("3-4, 7-8", coverage.gaps().to_string());
+        assert_eq!("5-5", coverage.crowded.to_string());
+        assert_eq!(vec![2], coverage.redundant);
+    }
+
+    #[test]
+    fn agrees_with_counting_every_section() {
+        gen::check_generated::<Day4>(|_, pairs| {
+            let elves = assignments(pairs);
+            let covering = |section: u64, except: Option<usize>| {
+                elves
+                    .iter()
+                    .enumerate()
+                    .filter(|&(idx, a)| {
+                        Some(idx) != except && a.first <= section && section <= a.last
+                    })
+                    .count()
+            };
+            let coverage = coverage(&elves, 2);
+            for section in 0..=100 {
+                assert_eq!(
+                    covering(section, None) > 0,
+                    coverage.covered.contains(section)
+                );
+                assert_eq!(
+                    covering(section, None) > 2,
+                    coverage.crowded.contains(section)
+                );
+            }
+            for (idx, elf) in elves.iter().enumerate() {
+                let redundant = (elf.first..=elf.last).all(|s| covering(s, Some(idx)) > 0);
+                assert_eq!(redundant, coverage.redundant.contains(&idx), "{elf:?}");
+            }
+        });
+    }
+}
@@ -0,0 +1,151 @@
+//! Looking up elves by the sections they're assigned, across every pair in the input.
+
+use std::fmt::Display;
+
+use super::CleaningRange;
+use crate::intervals::{Interval, IntervalTree};
+use crate::json::Json;
+
+/// An elf, counting from 0, so elf `n` is in pair `n / 2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Elf {
+    pub idx: usize,
+    pub range: CleaningRange,
+}
+
+impl Elf {
+    /// The line of the input the elf is on, counting from 1.
+    pub fn line(&self) -> usize {
+        self.idx / 2 + 1
+    }
+}
+
+/// Every elf's assignment, indexed by the sections in it.
+#[derive(Debug, Clone)]
+pub struct Roster {
+    tree: IntervalTree<Elf>,
+}
+
+impl Roster {
+    /// Indexes `assignments`, leaving out the reversed ones, which cover no sections.
+    pub fn new(assignments: &[CleaningRange]) -> Self {
+        let tree = assignments
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, &range)| range.interval().map(|i| (i, Elf { idx, range })))
+            .collect();
+        Self { tree }
+    }
+
+    /// The elves assigned `section`, in order.
+    pub fn covering(&self, section: u64) -> Vec<Elf> {
+        elves(self.tree.containing(section))
+    }
+
+    /// The elves assigned any section in `range`, in order.
+    pub fn overlapping(&self, range: &CleaningRange) -> Vec<Elf> {
+        match range.interval() {
+            Some(interval) => elves(self.tree.overlapping(&interval)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Every two elves assigned any of the same sections, in order, including the two in a pair.
+    pub fn overlapping_pairs(&self) -> Vec<(Elf, Elf)> {
+        let mut pairs: Vec<_> = self
+            .tree
+            .overlapping_pairs()
+            .into_iter()
+            .map(|((_, a), (_, b))| (*a.min(b), *a.max(b)))
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+fn elves(found: Vec<&(Interval, Elf)>) -> Vec<Elf> {
+    let mut elves: Vec<_> = found.into_iter().map(|&(_, elf)| elf).collect();
+    elves.sort_unstable();
+    elves
+}
+
+impl Display for Elf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Elf {} (line {}) on {}-{}",
+            self.idx + 1,
+            self.line(),
+            self.range.first,
+            self.range.last
+        )
+    }
+}
+
+impl From<&Elf> for Json {
+    fn from(elf: &Elf) -> Self {
+        Json::object()
+            .with("elf", elf.idx + 1)
+            .with("line", elf.line())
+            .with("sections", vec![elf.range.first, elf.range.last])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day4::{assignments, parse_pairs, Day4};
+    use crate::gen;
+
+    fn numbers(elves: &[Elf]) -> Vec<usize> {
+        elves.iter().map(|e| e.idx + 1).collect()
+    }
+
+    #[test]
+    fn looks_up_the_example() {
+        let pairs = parse_pairs("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8").unwrap();
+        let roster = Roster::new(&assignments(&pairs));
+        assert_eq!(vec![2, 6, 7, 12], numbers(&roster.covering(8)));
+        assert_eq!(vec![6], numbers(&roster.covering(9)));
+        assert!(roster.covering(1).is_empty());
+        let late = "8-20".parse().unwrap();
+        assert_eq!(vec![2, 6, 7, 12], numbers(&roster.overlapping(&late)));
+        let reversed = CleaningRange { first: 8, last: 2 };
+        assert!(roster.overlapping(&reversed).is_empty());
+        let pairs = roster.overlapping_pairs();
+        // All but 17 of the 66 pairs of elves share a section.
+        assert_eq!(66 - 17, pairs.len());
+        assert_eq!(
+            ("Elf 1 (line 1) on 2-4", "Elf 3 (line 2) on 2-3"),
+            (
+                pairs[0].0.to_string().as_str(),
+                pairs[0].1.to_string().as_str()
+            )
+        );
+    }
+
+    #[test]
+    fn agrees_with_checking_every_elf() {
+        gen::check_generated::<Day4>(|_, pairs| {
+            let elves = assignments(pairs);
+            let roster = Roster::new(&elves);
+            for section in 0..=100 {
+                let scanned: Vec<_> = (0..elves.len())
+                    .filter(|&e| elves[e].first <= section && section <= elves[e].last)
+                    .collect();
+                let found: Vec<_> = roster.covering(section).iter().map(|e| e.idx).collect();
+                assert_eq!(scanned, found);
+            }
+            let scanned: Vec<_> = (0..elves.len())
+                .flat_map(|a| (a + 1..elves.len()).map(move |b| (a, b)))
+                .filter(|&(a, b)| elves[a].overlaps(&elves[b]))
+                .collect();
+            let found: Vec<_> = roster
+                .overlapping_pairs()
+                .iter()
+                .map(|(a, b)| (a.idx, b.idx))
+                .collect();
+            assert_eq!(scanned, found);
+        });
+    }
+}
@@ -0,0 +1,424 @@
+use color_eyre::eyre::ContextCompat;
+use color_eyre::Result;
+use std::fmt::Display;
+
+use crate::gen::{Generator, Rng};
+use crate::json::Json;
+use crate::parse::{self, ParseError};
+use crate::{trace, Solution};
+
+#[derive(Debug, Clone)]
+pub struct Stacks {
+    /// Crates in each stack, starting from the bottom to the top
+    pub stacks: Vec<Vec<char>>,
+}
+
+impl Stacks {
+    pub fn new(stacks: usize) -> Self {
+        Stacks {
+            stacks: (0..stacks).map(|_| Vec::new()).collect(),
+        }
+    }
+
+    pub fn add_to_stack(&mut self, num: usize, c: char) {
+        self.stacks.get_mut(num - 1).unwrap().push(c);
+    }
+
+    pub fn move_crate(&mut self, times: usize, from: usize, to: usize) {
+        for _ in 0..times {
+            let c = self.stacks.get_mut(from - 1).unwrap().pop().unwrap();
+            self.stacks.get_mut(to - 1).unwrap().push(c);
+        }
+    }
+
+    pub fn move_crate_stack(&mut self, num: usize, from: usize, to: usize) {
+        let cratestack_len = self.stacks.get(from - 1).unwrap().len();
+        let mut cratestack = self
+            .stacks
+            .get_mut(from - 1)
+            .unwrap()
+            .split_off(cratestack_len - num);
+        assert_eq!(num, cratestack.len());
+        self.stacks.get_mut(to - 1).unwrap().append(&mut cratestack);
+    }
+
+    /// The crate on top of each stack, which fails if any stack is empty.
+    pub fn tops(&self) -> Result<String> {
+        self.stacks
+            .iter()
+            .enumerate()
+            .map(|(n, stack)| {
+                stack
+                    .last()
+                    .with_context(|| format!("Stack {} is empty", n + 1))
+            })
+            .collect()
+    }
+
+    pub fn get_crate(&self, from: usize, num: usize) -> Option<&char> {
+        self.stacks.get(from).and_then(|c| c.get(num))
+    }
+}
+
+impl From<&Stacks> for Json {
+    /// Each stack as a string of crates, from the bottom up.
+    fn from(stacks: &Stacks) -> Self {
+        stacks
+            .stacks
+            .iter()
+            .map(|s| s.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl Display for Stacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let num_stacks = self.stacks.len();
+        let tallest = self.stacks.iter().max_by_key(|x| x.len()).unwrap().len();
+        for height in (0..tallest).rev() {
+            for cr_idx in 0..num_stacks {
+                match self.get_crate(cr_idx, height) {
+                    Some(c) => write!(f, "[{c}] ")?,
+                    None => write!(f, "    ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        for cr_idx in 1..=num_stacks {
+            write!(f, " {cr_idx}  ")?;
+        }
+        Ok(())
+    }
+}
+
+/// A step of the rearrangement procedure, with stacks numbered from 1.
+#[derive(Debug, Clone, Copy)]
+pub struct CrateMove {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// The starting stacks and the moves the crane operator will make.
+#[derive(Debug, Clone)]
+pub struct Procedure {
+    pub stacks: Stacks,
+    pub moves: Vec<CrateMove>,
+}
+
+fn parse_move(line: &str, num_stacks: usize) -> Result<CrateMove, ParseError> {
+    let tokens: Vec<&str> = line.split(' ').collect();
+    let [move_kw, count, from_kw, from, to_kw, to] = tokens[..] else {
+        return Err(ParseError::new("Expected 'move N from A to B'", line));
+    };
+    for (token, keyword) in [(move_kw, "move"), (from_kw, "from"), (to_kw, "to")] {
+        if token != keyword {
+            return Err(ParseError::at(format!("Expected '{keyword}'"), line, token));
+        }
+    }
+    let count = parse::number(count, "The number of crates must be a whole number")
+        .map_err(|e| e.within(line, count))?;
+    let stack = |n: &str| {
+        let num: usize = parse::number(n, "Stacks are numbered").map_err(|e| e.within(line, n))?;
+        if num == 0 || num > num_stacks {
+            return Err(ParseError::at(
+                format!("Stacks are numbered from 1 to {num_stacks}"),
+                line,
+                n,
+            ));
+        }
+        Ok(num)
+    };
+    Ok(CrateMove {
+        count,
+        from: stack(from)?,
+        to: stack(to)?,
+    })
+}
+
+pub fn parse_procedure(s: &str) -> Result<Procedure, ParseError> {
+    let mut iter = s.lines().enumerate();
+
+    // Get the crates into the stacks
+    let mut crates_lines_upsidedown: Vec<(usize, &str)> = Vec::new();
+    let mut numbers_line = None;
+    for (idx, line) in iter.by_ref() {
+        match line.get(1..=1) {
+            Some("1") => {
+                numbers_line = Some(line);
+                break;
+            }
+            Some(_) => crates_lines_upsidedown.push((idx, line)),
+            None => {
+                return Err(
+                    ParseError::new("Expected a row of crates or the stack numbers", line)
+                        .on_line(idx + 1, line),
+                )
+            }
+        }
+    }
+    let numbers_line = numbers_line.ok_or_else(|| {
+        let last = s.lines().last().unwrap_or_default();
+        ParseError::new("Missing the line numbering the stacks", last)
+            .on_line(s.lines().count().max(1), last)
+    })?;
+
+    crates_lines_upsidedown.reverse();
+    let num_stacks = numbers_line.split_whitespace().count();
+
+    let mut stacks = Stacks::new(num_stacks);
+
+    for (idx, line) in crates_lines_upsidedown {
+        // The last stack's crate ends 4 * num_stacks - 1 characters in.
+        if let Some(rest) = line.get(4 * num_stacks - 1..) {
+            let extra = rest.trim();
+            if !extra.is_empty() {
+                return Err(ParseError::at(
+                    format!("Crate past the last stack, {num_stacks}"),
+                    line,
+                    extra,
+                )
+                .on_line(idx + 1, line));
+            }
+        }
+        for num in 0..num_stacks {
+            let index = 4 * num + 1;
+            if let Some(c) = line.get(index..=index) {
+                if c == " " {
+                    continue;
+                }
+                trace!("Adding {c} to stack {}", num + 1);
+                stacks.add_to_stack(num + 1, c.chars().next().unwrap());
+            }
+        }
+    }
+
+    if let Some((idx, line)) = iter.next() {
+        if !line.is_empty() {
+            return Err(ParseError::new(
+                "Expected a blank line between the crates and the moves",
+                line,
+            )
+            .on_line(idx + 1, line));
+        }
+    }
+
+    // Both cranes move the same number of crates, so the stacks are as tall either way.
+    let mut heights: Vec<usize> = stacks.stacks.iter().map(Vec::len).collect();
+    let mut moves = Vec::new();
+    for (idx, move_line) in iter {
+        let m = parse_move(move_line, num_stacks).map_err(|e| e.on_line(idx + 1, move_line))?;
+        let height = heights[m.from - 1];
+        if m.count > height {
+            let count = move_line.split(' ').nth(1).unwrap_or(move_line);
+            return Err(ParseError::at(
+                match height {
+                    1 => format!("Stack {} only has 1 crate by then", m.from),
+                    _ => format!("Stack {} only has {height} crates by then", m.from),
+                },
+                move_line,
+                count,
+            )
+            .on_line(idx + 1, move_line));
+        }
+        heights[m.from - 1] -= m.count;
+        heights[m.to - 1] += m.count;
+        moves.push(m);
+    }
+
+    Ok(Procedure { stacks, moves })
+}
+
+/// Carries out every move of the procedure with `crane`, returning the stacks at the end.
+pub fn rearrange(procedure: &Procedure, crane: fn(&mut Stacks, usize, usize, usize)) -> Stacks {
+    let mut stacks = procedure.stacks.clone();
+    for m in &procedure.moves {
+        crane(&mut stacks, m.count, m.from, m.to);
+    }
+    stacks
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Procedure;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_procedure(input)?)
+    }
+
+    fn part1(procedure: &Self::Input) -> Result<String> {
+        rearrange(procedure, Stacks::move_crate).tops()
+    }
+
+    fn part2(procedure: &Self::Input) -> Result<String> {
+        rearrange(procedure, Stacks::move_crate_stack).tops()
+    }
+
+    fn details(procedure: &Self::Input) -> Result<Json> {
+        Ok(Json::object()
+            .with("initial_stacks", &procedure.stacks)
+            .with("part1_stacks", &rearrange(procedure, Stacks::move_crate))
+            .with(
+                "part2_stacks",
+                &rearrange(procedure, Stacks::move_crate_stack),
+            ))
+    }
+}
+
+impl Generator for Day5 {
+    /// Two to nine stacks, and `size` moves that never empty a stack.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let num_stacks = rng.between(2, 9) as usize;
+        let mut heights: Vec<usize> = (0..num_stacks)
+            .map(|_| rng.between(1, 8) as usize)
+            .collect();
+        // With more crates than stacks some stack can always spare one.
+        heights[rng.below(num_stacks)] += 1;
+
+        let mut s = String::new();
+        for level in (0..*heights.iter().max().unwrap()).rev() {
+            let row: Vec<_> = heights
+                .iter()
+                .map(|&h| match h > level {
+                    true => format!("[{}]", (b'A' + rng.below(26) as u8) as char),
+                    false => "   ".to_owned(),
+                })
+                .collect();
+            s += &format!("{}\n", row.join(" "));
+        }
+        let numbers: Vec<_> = (1..=num_stacks).map(|n| format!(" {n} ")).collect();
+        s += &format!("{}\n\n", numbers.join(" "));
+
+        for _ in 0..size {
+            let spare: Vec<_> = (0..num_stacks).filter(|&n| heights[n] > 1).collect();
+            let from = *rng.pick(&spare);
+            let to = (from + 1 + rng.below(num_stacks - 1)) % num_stacks;
+            let count = rng.between(1, heights[from] as u64 - 1) as usize;
+            heights[from] -= count;
+            heights[to] += count;
+            s += &format!("move {count} from {} to {}\n", from + 1, to + 1);
+        }
+        s
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        let (diagram, moves) = input.split_once("\n\n").unwrap();
+        let mut rows: Vec<&[u8]> = diagram.lines().map(str::as_bytes).collect();
+        let num_stacks = rows.pop().unwrap().len().div_ceil(4);
+        let mut stacks = vec![Vec::new(); num_stacks];
+        for row in rows.iter().rev() {
+            for (n, stack) in stacks.iter_mut().enumerate() {
+                if row[4 * n + 1] != b' ' {
+                    stack.push(row[4 * n + 1] as char);
+                }
+            }
+        }
+        let mut part1 = stacks.clone();
+        let mut part2 = stacks;
+        for line in moves.lines() {
+            let n: Vec<usize> = line.split(' ').filter_map(|t| t.parse().ok()).collect();
+            let (count, from, to) = (n[0], n[1] - 1, n[2] - 1);
+            for _ in 0..count {
+                let c = part1[from].pop().unwrap();
+                part1[to].push(c);
+            }
+            let at = part2[from].len() - count;
+            let moved: Vec<char> = part2[from].drain(at..).collect();
+            part2[to].extend(moved);
+        }
+        let tops = |stacks: Vec<Vec<char>>| stacks.iter().map(|s| s.last().unwrap()).collect();
+        [Some(tops(part1)), Some(tops(part2))]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day5>(Part::Both);
+    }
+
+    #[test]
+    fn rejects_moving_more_crates_than_a_stack_has() {
+        let err = parse_procedure("    [D]\n[N] [C]\n 1   2\n\nmove 5 from 1 to 2").unwrap_err();
+        assert_eq!("Stack 1 only has 1 crate by then", err.message);
+        assert_eq!((Some(5), 6), (err.line, err.column));
+        // The first move leaves stack 2 with 3 crates, not enough for the second.
+        let err =
+            parse_procedure("    [D]\n[N] [C]\n 1   2\n\nmove 1 from 1 to 2\nmove 4 from 2 to 1")
+                .unwrap_err();
+        assert_eq!(
+            ("Stack 2 only has 3 crates by then".to_owned(), Some(6)),
+            (err.message, err.line)
+        );
+    }
+
+    #[test]
+    fn rejects_a_missing_blank_line() {
+        let err = parse_procedure("[N] [C]\n 1   2\nmove 1 from 1 to 2").unwrap_err();
+        assert_eq!(
+            "Expected a blank line between the crates and the moves",
+            err.message
+        );
+        assert_eq!(Some(3), err.line);
+    }
+
+    #[test]
+    fn rejects_crates_past_the_last_stack() {
+        let err = parse_procedure("[N] [C] [Z]\n 1   2\n\nmove 1 from 1 to 2").unwrap_err();
+        assert_eq!("Crate past the last stack, 2", err.message);
+        assert_eq!((Some(1), 9), (err.line, err.column));
+    }
+
+    #[test]
+    fn reports_empty_stacks_instead_of_their_tops() {
+        let procedure = parse_procedure("    [D]\n[N] [C]\n 1   2\n\nmove 1 from 1 to 2").unwrap();
+        let err = Day5::part1(&procedure).unwrap_err();
+        assert_eq!("Stack 1 is empty", err.to_string());
+        assert!(Day5::part2(&procedure).is_err());
+    }
+
+    #[test]
+    fn moving_conserves_crates() {
+        let count = |stacks: &Stacks| stacks.stacks.iter().map(Vec::len).sum::<usize>();
+        gen::check_generated::<Day5>(|_, procedure| {
+            let total = count(&procedure.stacks);
+            let mut one_at_a_time = procedure.stacks.clone();
+            let mut all_at_once = procedure.stacks.clone();
+            for m in &procedure.moves {
+                one_at_a_time.move_crate(m.count, m.from, m.to);
+                all_at_once.move_crate_stack(m.count, m.from, m.to);
+                assert_eq!(total, count(&one_at_a_time));
+                assert_eq!(total, count(&all_at_once));
+                for (a, b) in one_at_a_time.stacks.iter().zip(&all_at_once.stacks) {
+                    assert_eq!(a.len(), b.len());
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn moving_crates_one_by_one_is_the_same_either_way() {
+        gen::check_generated::<Day5>(|_, procedure| {
+            let mut one_at_a_time = procedure.stacks.clone();
+            let mut in_stacks_of_one = procedure.stacks.clone();
+            for m in &procedure.moves {
+                one_at_a_time.move_crate(m.count, m.from, m.to);
+                for _ in 0..m.count {
+                    in_stacks_of_one.move_crate_stack(1, m.from, m.to);
+                }
+                assert_eq!(one_at_a_time.stacks, in_stacks_of_one.stacks);
+            }
+        });
+    }
+}
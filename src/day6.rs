@@ -0,0 +1,114 @@
+use color_eyre::{eyre::eyre, Result};
+use std::collections::HashSet;
+
+use crate::gen::{Generator, Rng};
+use crate::json::Json;
+use crate::Solution;
+
+/// Number of different characters in a row that mark the start of a packet.
+pub const PACKET_MARKER_LEN: usize = 4;
+/// Number of different characters in a row that mark the start of a message.
+pub const MESSAGE_MARKER_LEN: usize = 14;
+
+/// Returns the number of characters read from `line` once the last `unique_chars` characters
+/// were all different, or None if that never happens.
+pub fn marker_end(line: &str, unique_chars: usize) -> Option<usize> {
+    let chars: Vec<char> = line.chars().collect();
+    chars
+        .windows(unique_chars)
+        .position(|marker| marker.iter().collect::<HashSet<_>>().len() == unique_chars)
+        .map(|start| start + unique_chars)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    /// The datastream buffer.
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim_end().to_owned())
+    }
+
+    fn part1(stream: &Self::Input) -> Result<usize> {
+        marker_end(stream, PACKET_MARKER_LEN).ok_or(eyre!("No start-of-packet marker found"))
+    }
+
+    fn part2(stream: &Self::Input) -> Result<usize> {
+        marker_end(stream, MESSAGE_MARKER_LEN).ok_or(eyre!("No start-of-message marker found"))
+    }
+
+    fn details(stream: &Self::Input) -> Result<Json> {
+        let marker = |len| {
+            marker_end(stream, len)
+                .map(|end| stream.chars().skip(end - len).take(len).collect::<String>())
+        };
+        Ok(Json::object()
+            .with("packet_marker", marker(PACKET_MARKER_LEN))
+            .with("message_marker", marker(MESSAGE_MARKER_LEN)))
+    }
+}
+
+impl Generator for Day6 {
+    /// A stream of `size` characters, ending with the start of a message if nothing before it is.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        // Too few different letters early on to ever find a message marker there.
+        let alphabet = rng.between(3, MESSAGE_MARKER_LEN as u64 - 1) as u8;
+        let mut s: String = (0..size.saturating_sub(MESSAGE_MARKER_LEN))
+            .map(|_| (b'a' + rng.below(alphabet as usize) as u8) as char)
+            .collect();
+        let mut marker: Vec<char> = ('a'..='z').collect();
+        rng.shuffle(&mut marker);
+        s.extend(&marker[..MESSAGE_MARKER_LEN]);
+        s.push('\n');
+        s
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        let chars: Vec<char> = input.trim_end().chars().collect();
+        let marker = |len| {
+            chars.windows(len).position(|w| {
+                let mut w = w.to_vec();
+                w.sort_unstable();
+                w.dedup();
+                w.len() == len
+            })
+        };
+        [PACKET_MARKER_LEN, MESSAGE_MARKER_LEN]
+            .map(|len| marker(len).map(|p| (p + len).to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day6>(Part::Both);
+    }
+
+    #[test]
+    fn counts_characters_rather_than_bytes() {
+        assert_eq!(Some(6), marker_end("ééaébcd", 4));
+        assert_eq!(None, marker_end("ééaé", 4));
+    }
+
+    #[test]
+    fn marker_is_the_first_distinct_run() {
+        let distinct = |s: &str| s.chars().collect::<HashSet<_>>().len() == s.len();
+        gen::check_generated::<Day6>(|_, stream| {
+            for len in [PACKET_MARKER_LEN, MESSAGE_MARKER_LEN] {
+                let end = marker_end(stream, len).unwrap();
+                assert!(distinct(&stream[end - len..end]));
+                assert!((len..end).all(|e| !distinct(&stream[e - len..e])));
+            }
+        });
+    }
+}
@@ -0,0 +1,380 @@
+use color_eyre::{eyre::eyre, Result};
+use parking_lot::Mutex;
+use std::{collections::HashMap, sync::Arc};
+
+use crate::gen::{Generator, Rng};
+use crate::json::Json;
+use crate::parse::{self, ParseError};
+use crate::Solution;
+
+/// Total size of the device's filesystem.
+pub const FILESYSTEM_SPACE: usize = 70_000_000;
+/// Unused space the update needs.
+pub const NEEDED_SPACE: usize = 30_000_000;
+
+pub struct File {
+    pub name: String,
+    pub size: usize,
+}
+
+pub trait ByteCount {
+    fn bytes(&self) -> usize;
+}
+
+impl ByteCount for File {
+    fn bytes(&self) -> usize {
+        self.size
+    }
+}
+
+pub struct Directory {
+    pub name: String,
+    pub files: HashMap<String, File>,
+    pub directories: Vec<Arc<Mutex<Directory>>>,
+}
+
+impl Directory {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            files: HashMap::new(),
+            directories: Vec::new(),
+        }
+    }
+
+    /// Add a file to the directory.  Does _not_ overwrite files.
+    pub fn add_file(&mut self, name: String, size: usize) {
+        self.files
+            .entry(name.clone())
+            .or_insert(File { name, size });
+    }
+
+    pub fn add_directory(&mut self, name: String) -> Arc<Mutex<Directory>> {
+        let dir = Directory::new(name);
+        let new_dir = Arc::new(Mutex::new(dir));
+        self.directories.push(new_dir.clone());
+        new_dir
+    }
+
+    pub fn get_or_add_directory(&mut self, name: String) -> Arc<Mutex<Directory>> {
+        {
+            let mut iter = self.directories.iter();
+            if let Some(d) = iter.find(|d| d.lock().name == name) {
+                return d.clone();
+            }
+        }
+        self.add_directory(name)
+    }
+
+    pub fn walk_children(&self) -> Vec<Arc<Mutex<Directory>>> {
+        let mut vec: Vec<_> = self.directories.to_vec();
+        for directory in &self.directories {
+            vec.append(&mut directory.lock().walk_children());
+        }
+        vec
+    }
+}
+
+impl ByteCount for Directory {
+    fn bytes(&self) -> usize {
+        self.files.values().map(ByteCount::bytes).sum::<usize>()
+            + self
+                .directories
+                .iter()
+                .map(|d| d.lock().bytes())
+                .sum::<usize>()
+    }
+}
+
+/// Reads the terminal transcript and builds the directory tree, returning the root.
+pub fn parse_transcript(s: &str) -> Result<Arc<Mutex<Directory>>, ParseError> {
+    let root = Arc::new(Mutex::new(Directory::new("/".to_owned())));
+
+    let mut current_dir = root.clone();
+    let mut parent_dir_stack = Vec::new();
+    // Read the directory tree and build it.
+    for (idx, line) in s.lines().enumerate() {
+        let err =
+            |message: &str, span: &str| ParseError::at(message, line, span).on_line(idx + 1, line);
+        let end = &line[line.len()..];
+        let mut tokens = line.split(' ');
+        match tokens.next() {
+            // A Command is happening
+            Some("$") => {
+                match match tokens.next().unwrap_or(end) {
+                    // Nothing to do, we'll parse the files as they come on subsequent lines.
+                    "ls" => continue,
+                    "cd" => tokens
+                        .next()
+                        .ok_or_else(|| err("cd needs a directory", end))?,
+                    unkn => return Err(err("Only cd and ls commands are known", unkn)),
+                } {
+                    "/" => {
+                        current_dir = root.clone();
+                        parent_dir_stack = Vec::new();
+                    }
+                    ".." => {
+                        current_dir = parent_dir_stack
+                            .pop()
+                            .ok_or_else(|| err("Already at the root directory", line))?
+                    }
+                    dirname => {
+                        let new_dir = current_dir.lock().get_or_add_directory(dirname.to_owned());
+                        let old_current = std::mem::replace(&mut current_dir, new_dir);
+                        parent_dir_stack.push(old_current);
+                    }
+                }
+            }
+            // A directory listing
+            Some("dir") => {
+                let dirname = tokens
+                    .next()
+                    .ok_or_else(|| err("Missing the directory name", end))?;
+                current_dir.lock().add_directory(dirname.to_owned());
+            }
+            // A file listing
+            Some(nums) => {
+                let size = parse::number(nums, "Expected a command, a directory or a file size")
+                    .map_err(|e| e.within(line, nums).on_line(idx + 1, line))?;
+                let filename = tokens
+                    .next()
+                    .ok_or_else(|| err("Missing the file name", end))?;
+                current_dir.lock().add_file(filename.to_owned(), size);
+            }
+            None => unreachable!("split always returns at least one token"),
+        }
+    }
+
+    Ok(root)
+}
+
+/// The full path and size of `dir` and every directory beneath it, parents first.
+pub fn dir_sizes(dir: &Arc<Mutex<Directory>>, path: &str) -> Vec<(String, usize)> {
+    let dir = dir.lock();
+    let mut sizes = vec![(path.to_owned(), dir.bytes())];
+    for child in &dir.directories {
+        let name = child.lock().name.clone();
+        sizes.append(&mut dir_sizes(
+            child,
+            &format!("{}/{name}", path.trim_end_matches('/')),
+        ));
+    }
+    sizes
+}
+
+/// `root` and every directory beneath it.
+pub fn all_directories(root: &Arc<Mutex<Directory>>) -> Vec<Arc<Mutex<Directory>>> {
+    let mut dirs = vec![root.clone()];
+    dirs.append(&mut root.lock().walk_children());
+    dirs
+}
+
+/// Space that has to be freed before the update fits on the filesystem.
+pub fn space_to_free(root: &Arc<Mutex<Directory>>) -> usize {
+    let total_taken_space = root.lock().bytes();
+    NEEDED_SPACE.saturating_sub(FILESYSTEM_SPACE.saturating_sub(total_taken_space))
+}
+
+/// Finds the smallest directory, possibly the root, that frees up enough space when deleted, and
+/// its size.
+pub fn dir_to_delete(root: &Arc<Mutex<Directory>>) -> Option<(Arc<Mutex<Directory>>, usize)> {
+    let to_delete_space = space_to_free(root);
+    all_directories(root)
+        .iter()
+        .map(|d| (d.clone(), d.lock().bytes()))
+        .filter(|(_d, bytes)| *bytes >= to_delete_space)
+        .min_by_key(|(_d, bytes)| *bytes)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Arc<Mutex<Directory>>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_transcript(input)?)
+    }
+
+    fn part1(root: &Self::Input) -> Result<usize> {
+        Ok(all_directories(root)
+            .iter()
+            .map(|d| d.lock().bytes())
+            .filter(|&bytes| bytes <= 100_000)
+            .sum::<usize>())
+    }
+
+    fn part2(root: &Self::Input) -> Result<usize> {
+        let (_dir, size) = dir_to_delete(root).ok_or(eyre!("No directory is big enough"))?;
+        Ok(size)
+    }
+
+    fn details(root: &Self::Input) -> Result<Json> {
+        let directories: Vec<_> = dir_sizes(root, "/")
+            .into_iter()
+            .map(|(path, bytes)| Json::object().with("path", path).with("bytes", bytes))
+            .collect();
+        let used = root.lock().bytes();
+        Ok(Json::object()
+            .with("used", used)
+            .with("to_free", space_to_free(root))
+            .with("directories", directories))
+    }
+}
+
+/// A directory made up for a generated transcript.
+struct GenDir {
+    name: String,
+    files: Vec<(String, u64)>,
+    dirs: Vec<GenDir>,
+}
+
+impl GenDir {
+    /// Adds an entry named something none of its siblings are.
+    fn add(&mut self, rng: &mut Rng, is_dir: bool, max_file: u64) {
+        let name = loop {
+            let name = match is_dir {
+                true => rng.word(1, 8),
+                false => format!("{}.{}", rng.word(1, 8), rng.word(1, 3)),
+            };
+            let taken = |n: &String| *n == name;
+            if !self.files.iter().map(|f| &f.0).any(taken)
+                && !self.dirs.iter().map(|d| &d.name).any(taken)
+            {
+                break name;
+            }
+        };
+        match is_dir {
+            true => self.dirs.push(GenDir {
+                name,
+                files: Vec::new(),
+                dirs: Vec::new(),
+            }),
+            false => self.files.push((name, rng.between(1, max_file))),
+        }
+    }
+
+    /// Somewhere in this tree, picking deeper directories less often.
+    fn random_dir(&mut self, rng: &mut Rng) -> &mut GenDir {
+        match rng.below(self.dirs.len() + 1) {
+            0 => self,
+            n => self.dirs[n - 1].random_dir(rng),
+        }
+    }
+
+    fn write(&self, s: &mut String) {
+        *s += "$ ls\n";
+        for dir in &self.dirs {
+            *s += &format!("dir {}\n", dir.name);
+        }
+        for (name, size) in &self.files {
+            *s += &format!("{size} {name}\n");
+        }
+        for dir in &self.dirs {
+            *s += &format!("$ cd {}\n", dir.name);
+            dir.write(s);
+            *s += "$ cd ..\n";
+        }
+    }
+}
+
+impl Generator for Day7 {
+    /// A transcript exploring `size` files and directories, filling a good part of the disk.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut root = GenDir {
+            name: "/".to_owned(),
+            files: Vec::new(),
+            dirs: Vec::new(),
+        };
+        // About two thirds of the entries are files, half the maximum size on average, which
+        // comes to roughly 55,000,000 bytes, so there's always something to delete.
+        let max_file = (165_000_000 / size.max(1) as u64).clamp(1, FILESYSTEM_SPACE as u64 / 2);
+        for _ in 0..size.max(1) {
+            let is_dir = rng.one_in(3);
+            root.random_dir(rng).add(rng, is_dir, max_file);
+        }
+        let mut s = "$ cd /\n".to_owned();
+        root.write(&mut s);
+        s
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        let mut path = Vec::new();
+        let mut sizes: HashMap<Vec<&str>, usize> = HashMap::new();
+        for line in input.lines() {
+            match line.split(' ').collect::<Vec<_>>()[..] {
+                ["$", "cd", "/"] => path.clear(),
+                ["$", "cd", ".."] => {
+                    path.pop();
+                }
+                ["$", "cd", dir] => path.push(dir),
+                ["$", "ls"] => {}
+                ["dir", dir] => {
+                    let mut dir_path = path.clone();
+                    dir_path.push(dir);
+                    sizes.entry(dir_path).or_default();
+                }
+                [size, _] => {
+                    for depth in 0..=path.len() {
+                        *sizes.entry(path[..depth].to_vec()).or_default() +=
+                            size.parse::<usize>().unwrap();
+                    }
+                }
+                _ => panic!("Unexpected line {line}"),
+            }
+        }
+        let part1: usize = sizes.values().filter(|&&s| s <= 100_000).sum();
+        let used = sizes.get(&Vec::new()).copied().unwrap_or_default();
+        let needed = NEEDED_SPACE.saturating_sub(FILESYSTEM_SPACE.saturating_sub(used));
+        let part2 = sizes.values().filter(|&&s| s >= needed).min();
+        [Some(part1.to_string()), part2.map(usize::to_string)]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day7>(Part::Both);
+    }
+
+    #[test]
+    fn counts_the_root_and_directories_right_at_the_limits() {
+        // The root is a directory like any other, and holds exactly 100,000 bytes.
+        let root = parse_transcript("$ cd /\n$ ls\ndir a\n99999 b\n$ cd a\n$ ls\n1 c").unwrap();
+        assert_eq!(100_000 + 1, Day7::part1(&root).unwrap());
+        // Deleting `a` frees exactly what's needed, and is the smallest that does.
+        let root =
+            parse_transcript("$ cd /\n$ ls\ndir a\n40000000 b\n$ cd a\n$ ls\n10000000 c").unwrap();
+        assert_eq!(10_000_000, space_to_free(&root));
+        assert_eq!(10_000_000, Day7::part2(&root).unwrap());
+        // Only deleting everything frees enough.
+        let root = parse_transcript("$ cd /\n$ ls\ndir a\n45000000 b\n$ cd a\n$ ls\n1 c").unwrap();
+        assert_eq!(45_000_001, Day7::part2(&root).unwrap());
+    }
+
+    #[test]
+    fn directory_bytes_are_its_files() {
+        gen::check_generated::<Day7>(|transcript, root| {
+            let listed: usize = transcript
+                .lines()
+                .filter_map(|l| l.split(' ').next()?.parse::<usize>().ok())
+                .sum();
+            assert_eq!(listed, root.lock().bytes());
+            for dir in all_directories(root) {
+                let files_beneath: usize = all_directories(&dir)
+                    .iter()
+                    .flat_map(|d| d.lock().files.values().map(|f| f.size).collect::<Vec<_>>())
+                    .sum();
+                assert_eq!(files_beneath, dir.lock().bytes());
+            }
+        });
+    }
+}
@@ -0,0 +1,320 @@
+use color_eyre::Result;
+
+use crate::gen::{Generator, Rng};
+use crate::json::Json;
+use crate::parse::{self, ParseError};
+use crate::{debug, trace, Solution};
+
+pub struct Grid {
+    pub size: usize,
+    pub heights: Vec<Vec<u8>>,
+}
+
+impl Grid {
+    pub fn get_height(&self, row: usize, col: usize) -> Option<u8> {
+        self.heights.get(row).and_then(|r| r.get(col)).copied()
+    }
+
+    /// The tree one step from `(row, col)` in direction `(dx, dy)`, if it's still in the grid.
+    fn step(&self, row: usize, col: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
+        let row = row.checked_add_signed(dx)?;
+        let col = col.checked_add_signed(dy)?;
+        (row < self.size && col < self.size).then_some((row, col))
+    }
+
+    pub fn lower_than_dir(&self, row: usize, col: usize, height: u8, dx: isize, dy: isize) -> bool {
+        let Some((nextx, nexty)) = self.step(row, col, dx, dy) else {
+            return true;
+        };
+        let next_height = self.get_height(nextx, nexty).unwrap();
+        if next_height >= height {
+            return false;
+        }
+        self.lower_than_dir(nextx, nexty, height, dx, dy)
+    }
+
+    pub fn on_edge(&self, row: usize, col: usize) -> bool {
+        row == 0 || col == 0 || row == (self.size - 1) || col == (self.size - 1)
+    }
+
+    pub fn is_visible(&self, row: usize, col: usize) -> bool {
+        if self.on_edge(row, col) {
+            return true;
+        }
+        let cur_height = self.get_height(row, col).unwrap();
+
+        self.lower_than_dir(row, col, cur_height, -1, 0)
+            || self.lower_than_dir(row, col, cur_height, 1, 0)
+            || self.lower_than_dir(row, col, cur_height, 0, -1)
+            || self.lower_than_dir(row, col, cur_height, 0, 1)
+    }
+
+    pub fn count_until_lower(
+        &self,
+        row: usize,
+        col: usize,
+        dx: isize,
+        dy: isize,
+        height: u8,
+    ) -> usize {
+        let Some((newrow, newcol)) = self.step(row, col, dx, dy) else {
+            return 0;
+        };
+        let here_height = self.get_height(newrow, newcol).unwrap();
+        if here_height >= height {
+            return 1;
+        }
+        1 + self.count_until_lower(newrow, newcol, dx, dy, height)
+    }
+
+    pub fn trees_visible_score(&self, row: usize, col: usize) -> usize {
+        let tree_height = self.get_height(row, col).unwrap();
+        self.count_until_lower(row, col, -1, 0, tree_height)
+            * self.count_until_lower(row, col, 1, 0, tree_height)
+            * self.count_until_lower(row, col, 0, -1, tree_height)
+            * self.count_until_lower(row, col, 0, 1, tree_height)
+    }
+}
+
+pub fn parse_grid(s: &str) -> Result<Grid, ParseError> {
+    let vec = parse::parse_lines(s, |line| {
+        line.char_indices()
+            .map(|(idx, x)| {
+                x.to_digit(10).map(|h| h as u8).ok_or_else(|| {
+                    ParseError::at(
+                        "Tree heights are digits",
+                        line,
+                        &line[idx..idx + x.len_utf8()],
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+    let size = vec.first().map_or(0, Vec::len);
+    if size == 0 {
+        return Err(ParseError::new("The grid is empty", "").on_line(1, ""));
+    }
+    for (idx, (row, line)) in vec.iter().zip(s.lines()).enumerate() {
+        if row.len() != size {
+            return Err(ParseError::new(
+                format!("Every row should have {size} trees, like the first"),
+                line,
+            )
+            .on_line(idx + 1, line));
+        }
+    }
+    if vec.len() != size {
+        let last = s.lines().last().unwrap_or_default();
+        return Err(ParseError::new(
+            format!(
+                "The grid should be square, but has {size} columns and {} rows",
+                vec.len()
+            ),
+            last,
+        )
+        .on_line(vec.len(), last));
+    }
+    Ok(Grid { size, heights: vec })
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Grid;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_grid(input)?)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<usize> {
+        let mut visible_trees = 0;
+        for x in 0..grid.size {
+            for y in 0..grid.size {
+                if grid.is_visible(x, y) {
+                    trace!("({x}, {y}) is visible");
+                    visible_trees += 1;
+                }
+            }
+        }
+        Ok(visible_trees)
+    }
+
+    fn part2(grid: &Self::Input) -> Result<usize> {
+        let mut max_visible_score = 0;
+        for x in 0..grid.size {
+            for y in 0..grid.size {
+                let score = grid.trees_visible_score(x, y);
+                if score > max_visible_score {
+                    debug!("({x}, {y}) has the best scenic score so far, {score}");
+                    max_visible_score = score;
+                }
+            }
+        }
+        Ok(max_visible_score)
+    }
+
+    fn details(grid: &Self::Input) -> Result<Json> {
+        let best = (0..grid.size)
+            .flat_map(|x| (0..grid.size).map(move |y| (x, y)))
+            .max_by_key(|&(x, y)| grid.trees_visible_score(x, y))
+            .map(|(x, y)| {
+                Json::object()
+                    .with("row", x)
+                    .with("col", y)
+                    .with("height", grid.get_height(x, y))
+                    .with("score", grid.trees_visible_score(x, y))
+            });
+        Ok(Json::object()
+            .with("size", grid.size)
+            .with("best_tree", best))
+    }
+}
+
+impl Generator for Day8 {
+    /// A square forest `size` trees across.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let size = size.max(1);
+        (0..size)
+            .map(|_| {
+                let row: String = (0..size)
+                    .map(|_| (b'0' + rng.below(10) as u8) as char)
+                    .collect();
+                row + "\n"
+            })
+            .collect()
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        let trees: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        let size = trees.len() as isize;
+        let (mut visible, mut best) = (0, 0);
+        for row in 0..size {
+            for col in 0..size {
+                let height = trees[row as usize][col as usize];
+                let (mut seen_from_outside, mut score) = (false, 1);
+                for (dr, dc) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    let (mut r, mut c, mut seen) = (row + dr, col + dc, 0);
+                    let mut blocked = false;
+                    while (0..size).contains(&r) && (0..size).contains(&c) {
+                        seen += 1;
+                        if trees[r as usize][c as usize] >= height {
+                            blocked = true;
+                            break;
+                        }
+                        r += dr;
+                        c += dc;
+                    }
+                    seen_from_outside |= !blocked;
+                    score *= seen;
+                }
+                visible += seen_from_outside as usize;
+                best = best.max(score);
+            }
+        }
+        [Some(visible.to_string()), Some(best.to_string())]
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    fn example_grid() -> Grid {
+        parse_grid(
+            "30373
+25512
+65332
+33549
+35390",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day8>(Part::Both);
+    }
+
+    #[test]
+    fn edges_are_visible() {
+        gen::check_generated::<Day8>(|_, grid| {
+            let edge = (0..grid.size)
+                .flat_map(|i| [(0, i), (i, 0), (grid.size - 1, i), (i, grid.size - 1)]);
+            for (row, col) in edge {
+                assert!(grid.is_visible(row, col));
+                assert_eq!(0, grid.trees_visible_score(row, col));
+            }
+        });
+    }
+
+    #[test]
+    fn counts_grids_wider_than_an_i8() {
+        let input = gen::generate::<Day8>(0, 200);
+        let grid = parse_grid(&input).unwrap();
+        let [visible, best] = Day8::reference(&input);
+        assert_eq!(visible, Some(Day8::part1(&grid).unwrap().to_string()));
+        assert_eq!(best, Some(Day8::part2(&grid).unwrap().to_string()));
+    }
+
+    #[test]
+    fn lower_than_dir_answers_right() {
+        let grid = example_grid();
+
+        assert!(!grid.lower_than_dir(1, 3, 1, -1, 0));
+        assert!(!grid.lower_than_dir(1, 3, 1, 1, 0));
+        assert!(!grid.lower_than_dir(1, 3, 1, 0, 1));
+        assert!(!grid.lower_than_dir(1, 3, 1, 0, -1));
+    }
+
+    #[test]
+    fn is_visible_answers_right() {
+        let grid = example_grid();
+        assert!(!grid.is_visible(1, 3));
+        assert!(grid.is_visible(2, 1));
+    }
+
+    #[test]
+    fn count_until_lower() {
+        let grid = example_grid();
+
+        assert_eq!(1, grid.count_until_lower(1, 2, -1, 0, 5));
+        assert_eq!(2, grid.count_until_lower(1, 2, 1, 0, 5));
+        assert_eq!(1, grid.count_until_lower(1, 2, 0, -1, 5));
+        assert_eq!(2, grid.count_until_lower(1, 2, 0, 1, 5));
+
+        assert_eq!(1, grid.count_until_lower(1, 1, -1, 0, 5));
+        assert_eq!(1, grid.count_until_lower(1, 1, 1, 0, 5));
+        assert_eq!(1, grid.count_until_lower(1, 1, 0, -1, 5));
+        assert_eq!(1, grid.count_until_lower(1, 1, 0, 1, 5));
+
+        assert_eq!(1, grid.count_until_lower(2, 2, -1, 0, 3));
+        assert_eq!(1, grid.count_until_lower(2, 2, 1, 0, 3));
+        assert_eq!(1, grid.count_until_lower(2, 2, 0, -1, 3));
+        assert_eq!(1, grid.count_until_lower(2, 2, 0, 1, 3));
+
+        assert_eq!(2, grid.count_until_lower(3, 2, -1, 0, 5));
+        assert_eq!(1, grid.count_until_lower(3, 2, 1, 0, 5));
+        assert_eq!(2, grid.count_until_lower(3, 2, 0, -1, 5));
+        assert_eq!(2, grid.count_until_lower(3, 2, 0, 1, 5));
+
+        assert_eq!(1, grid.count_until_lower(0, 3, 0, 1, 7));
+        assert_eq!(3, grid.count_until_lower(0, 3, 0, -1, 7));
+        assert_eq!(0, grid.count_until_lower(0, 3, -1, 0, 7));
+        assert_eq!(4, grid.count_until_lower(0, 3, 1, 0, 7));
+    }
+
+    #[test]
+    fn trees_visible_score() {
+        let grid = example_grid();
+
+        assert_eq!(4, grid.trees_visible_score(1, 2));
+        assert_eq!(8, grid.trees_visible_score(3, 2));
+    }
+}
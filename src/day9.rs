@@ -0,0 +1,337 @@
+use std::{collections::HashSet, str::FromStr};
+
+use color_eyre::Result;
+
+use crate::gen::{Generator, Rng};
+use crate::json::Json;
+use crate::parse::{self, ParseError};
+use crate::Solution;
+
+pub enum Direction {
+    Right,
+    Up,
+    Left,
+    Down,
+}
+
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "R" => Ok(Self::Right),
+            "U" => Ok(Self::Up),
+            "L" => Ok(Self::Left),
+            "D" => Ok(Self::Down),
+            _ => Err(ParseError::new("Directions are one of R, U, L or D", s)),
+        }
+    }
+}
+
+pub struct Move {
+    pub dir: Direction,
+    pub steps: u8,
+}
+
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (dir_str, steps_str) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new("Expected a direction and a number of steps", s))?;
+        Ok(Self {
+            dir: dir_str
+                .parse::<Direction>()
+                .map_err(|e| e.within(s, dir_str))?,
+            steps: parse::number(steps_str, "Steps are a number from 0 to 255")
+                .map_err(|e| e.within(s, steps_str))?,
+        })
+    }
+}
+
+pub fn parse_moves(s: &str) -> Result<Vec<Move>, ParseError> {
+    parse::parse_lines(s, str::parse)
+}
+
+#[derive(Default, Debug)]
+pub struct Board {
+    pub knot_locations: Vec<(i32, i32)>,
+}
+
+pub fn pairs_mut_each<T: std::fmt::Debug>(vec: &mut [T], f: fn(&mut T, &mut T)) {
+    for idx in 0..vec.len() - 1 {
+        let (front, back) = vec.split_at_mut(idx + 1);
+        let front_mut = front.last_mut().unwrap();
+        let back_mut = back.first_mut().unwrap();
+        f(front_mut, back_mut);
+    }
+}
+
+impl Board {
+    pub fn new(length: usize) -> Self {
+        Self {
+            knot_locations: (0..length).map(|_| (0, 0)).collect(),
+        }
+    }
+
+    pub fn tail_location(&self) -> (i32, i32) {
+        *self.knot_locations.last().unwrap()
+    }
+
+    /// Applies the move given. Returns the path that the Tail has taken.
+    pub fn apply(&mut self, m: &Move) -> Vec<(i32, i32)> {
+        let mut tail_pos = vec![self.tail_location()];
+
+        for _ in 0..m.steps {
+            tail_pos.push(self.go_dir(&m.dir));
+        }
+        tail_pos
+    }
+
+    /// Makes the head go in a direction, and then returns the position of the Tail afterwards.
+    pub fn go_dir(&mut self, d: &Direction) -> (i32, i32) {
+        let loc = self.knot_locations.first_mut().unwrap();
+        *loc = match d {
+            Direction::Right => (loc.0, loc.1 + 1),
+            Direction::Left => (loc.0, loc.1 - 1),
+            Direction::Up => (loc.0 - 1, loc.1),
+            Direction::Down => (loc.0 + 1, loc.1),
+        };
+        pairs_mut_each(&mut self.knot_locations, |head, tail| {
+            match (head.0 - tail.0, head.1 - tail.1) {
+                // T.H => .TH
+                // or T..     ..H
+                //    ..H  or T..
+                (0, 2) | (1, 2) | (-1, 2) => *tail = (head.0, head.1 - 1),
+                // H.T => HT
+                // or H..     ..T
+                //    ..T  or H..
+                (0, -2) | (1, -2) | (-1, -2) => *tail = (head.0, head.1 + 1),
+                // (same but vertically)
+                (2, 0) | (2, 1) | (2, -1) => *tail = (head.0 - 1, head.1),
+                (-2, 0) | (-2, 1) | (-2, -1) => *tail = (head.0 + 1, head.1),
+                // Diagonally far away
+                (-2, 2) => *tail = (head.0 + 1, head.1 - 1),
+                (2, 2) => *tail = (head.0 - 1, head.1 - 1),
+                (-2, -2) => *tail = (head.0 + 1, head.1 + 1),
+                (2, -2) => *tail = (head.0 - 1, head.1 + 1),
+                // Nothing to do, head is either covering or touxhing tail.
+                (0, 0)
+                | (1, 0)
+                | (0, 1)
+                | (1, 1)
+                | (-1, 0)
+                | (0, -1)
+                | (-1, 1)
+                | (1, -1)
+                | (-1, -1) => {}
+                _ => panic!(
+                    "Somehow the head and tail got too far apart: head = {:?}, tail = {:?}",
+                    head, tail
+                ),
+            }
+        });
+        *self.knot_locations.iter().last().unwrap()
+    }
+}
+
+pub fn part1(moves: &[Move]) -> usize {
+    let mut visited_tail = HashSet::new();
+
+    let mut board = Board::new(2);
+
+    for m in moves {
+        for loc in board.apply(m) {
+            visited_tail.insert(loc);
+        }
+    }
+    visited_tail.len()
+}
+
+pub fn part2(moves: &[Move]) -> usize {
+    let mut visited_tail = HashSet::new();
+    let mut board = Board::new(10);
+    for m in moves {
+        for loc in board.apply(m) {
+            visited_tail.insert(loc);
+        }
+    }
+    visited_tail.len()
+}
+
+/// Moves a rope of `length` knots, returning where each knot ends up, head first.
+pub fn final_knots(moves: &[Move], length: usize) -> Vec<(i32, i32)> {
+    let mut board = Board::new(length);
+    for m in moves {
+        board.apply(m);
+    }
+    board.knot_locations
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<Move>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_moves(input)?)
+    }
+
+    fn part1(moves: &Self::Input) -> Result<usize> {
+        Ok(part1(moves))
+    }
+
+    fn part2(moves: &Self::Input) -> Result<usize> {
+        Ok(part2(moves))
+    }
+
+    fn details(moves: &Self::Input) -> Result<Json> {
+        let knots = |length| {
+            final_knots(moves, length)
+                .into_iter()
+                .map(|(row, col)| vec![row, col])
+                .collect::<Vec<_>>()
+        };
+        Ok(Json::object()
+            .with("moves", moves.len())
+            .with("part1_knots", knots(2))
+            .with("part2_knots", knots(10)))
+    }
+}
+
+impl Generator for Day9 {
+    /// `size` moves of up to twenty steps.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1))
+            .map(|_| {
+                format!(
+                    "{} {}\n",
+                    rng.pick(&['U', 'D', 'L', 'R']),
+                    rng.between(1, 20)
+                )
+            })
+            .collect()
+    }
+
+    fn reference(input: &str) -> [Option<String>; 2] {
+        [2, 10].map(|length| {
+            let mut knots = vec![(0i32, 0i32); length];
+            let mut visited = HashSet::from([(0, 0)]);
+            for line in input.lines() {
+                let (dir, steps) = line.split_once(' ').unwrap();
+                let (dr, dc) = match dir {
+                    "U" => (-1, 0),
+                    "D" => (1, 0),
+                    "L" => (0, -1),
+                    _ => (0, 1),
+                };
+                for _ in 0..steps.parse().unwrap() {
+                    knots[0] = (knots[0].0 + dr, knots[0].1 + dc);
+                    for idx in 1..length {
+                        let (ahead, knot) = (knots[idx - 1], knots[idx]);
+                        // A knot that falls behind moves one step straight towards the one ahead.
+                        if (ahead.0 - knot.0).abs() > 1 || (ahead.1 - knot.1).abs() > 1 {
+                            knots[idx] = (
+                                knot.0 + (ahead.0 - knot.0).signum(),
+                                knot.1 + (ahead.1 - knot.1).signum(),
+                            );
+                        }
+                    }
+                    visited.insert(knots[length - 1]);
+                }
+            }
+            Some(visited.len().to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::gen;
+    use crate::runner::Part;
+
+    #[test]
+    fn matches_reference() {
+        gen::check_reference::<Day9>(Part::Both);
+    }
+
+    #[test]
+    fn knots_stay_together() {
+        gen::check_generated::<Day9>(|_, moves| {
+            let mut board = Board::new(10);
+            for m in moves {
+                for _ in 0..m.steps {
+                    board.go_dir(&m.dir);
+                    for pair in board.knot_locations.windows(2) {
+                        let (a, b) = (pair[0], pair[1]);
+                        assert!(
+                            (a.0 - b.0).abs() <= 1 && (a.1 - b.1).abs() <= 1,
+                            "{a:?} {b:?}"
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    fn example_moves() -> Vec<Move> {
+        let s = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2";
+        parse_moves(s).unwrap()
+    }
+
+    fn example_moves_longer() -> Vec<Move> {
+        let s = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20";
+        parse_moves(s).unwrap()
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(13, part1(&example_moves()));
+    }
+
+    #[test]
+    fn example_long() {
+        assert_eq!(1, part2(&example_moves()));
+    }
+
+    #[test]
+    fn rope_example() {
+        assert_eq!(36, part2(&example_moves_longer()));
+    }
+
+    #[test]
+    fn pairs_mut_works() {
+        let mut v = vec![0, 1, 2, 3, 4, 5];
+        pairs_mut_each(&mut v, |a, b| {
+            *a += 1;
+            *b += 1;
+        });
+        assert_eq!(1, v[0]);
+        assert_eq!(3, v[1]);
+        assert_eq!(4, v[2]);
+        assert_eq!(5, v[3]);
+        assert_eq!(6, v[4]);
+        assert_eq!(6, v[5]);
+    }
+}
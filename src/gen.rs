@@ -0,0 +1,164 @@
+//! Random puzzle inputs, to exercise the solutions beyond the examples and our own inputs.
+//!
+//! Every day implements [`Generator`], writing inputs in the same format as the puzzle's from a
+//! seed, so any input can be made again from its seed and size.
+
+use crate::Solution;
+
+/// How big generated inputs are unless asked otherwise.
+pub const DEFAULT_SIZE: usize = 100;
+
+/// A small seedable random number generator ([SplitMix64](https://prng.di.unimi.it/)), so the
+/// same seed gives the same input everywhere.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number from `low` to `high`, inclusive.
+    pub fn between(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low <= high, "{low} is more than {high}");
+        match (high - low).checked_add(1) {
+            Some(span) => low + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    /// An index below `n`, which must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        self.between(0, n as u64 - 1) as usize
+    }
+
+    /// True one time in `n`.
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.between(1, n) == 1
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.below(idx + 1));
+        }
+    }
+
+    /// A lowercase word of `low` to `high` letters.
+    pub fn word(&mut self, low: usize, high: usize) -> String {
+        let len = self.between(low as u64, high as u64);
+        (0..len)
+            .map(|_| (b'a' + self.below(26) as u8) as char)
+            .collect()
+    }
+}
+
+/// A day whose inputs can be made up.
+pub trait Generator: Solution {
+    /// Writes a random, valid input with roughly `size` items of whatever the day is about.
+    fn generate(rng: &mut Rng, size: usize) -> String;
+
+    /// Works out the answers to a generated input the slow, obvious way, without the solution, so
+    /// they can be checked against each other. `None` where there's no such way.
+    ///
+    /// May panic on anything [`Generator::generate`] wouldn't write.
+    fn reference(_input: &str) -> [Option<String>; 2] {
+        [None, None]
+    }
+}
+
+/// Generates an input for `S` from `seed`.
+pub fn generate<S: Generator>(seed: u64, size: usize) -> String {
+    S::generate(&mut Rng::new(seed), size)
+}
+
+/// Calls `check` with inputs generated for `S` from a range of seeds and sizes, and the parsed
+/// input, naming the seed and size of any that fail.
+#[cfg(test)]
+pub(crate) fn check_generated<S: Generator>(check: impl Fn(&str, &S::Input)) {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    for size in [1, 2, 5, 20, DEFAULT_SIZE] {
+        for seed in 0..10 {
+            let input = generate::<S>(seed, size);
+            let failed = match S::parse(&input) {
+                Ok(parsed) => catch_unwind(AssertUnwindSafe(|| check(&input, &parsed))).is_err(),
+                Err(e) => {
+                    eprintln!("{e}");
+                    true
+                }
+            };
+            if failed {
+                panic!(
+                    "Failed on `aoc gen --day {} --seed {seed} --size {size}`",
+                    S::DAY
+                );
+            }
+        }
+    }
+}
+
+/// Checks the solution to `part` against [`Generator::reference`] on generated inputs.
+#[cfg(test)]
+pub(crate) fn check_reference<S: Generator>(part: crate::runner::Part) {
+    check_generated::<S>(|input, parsed| {
+        let [part1, part2] = S::reference(input);
+        if let Some(expected) = part1.filter(|_| part.includes_one()) {
+            assert_eq!(expected, S::part1(parsed).unwrap().to_string(), "part 1");
+        }
+        if let Some(expected) = part2.filter(|_| part.includes_two()) {
+            assert_eq!(expected, S::part2(parsed).unwrap().to_string(), "part 2");
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn between_stays_in_range() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let n = rng.between(3, 5);
+            assert!((3..=5).contains(&n));
+        }
+        assert_eq!(4, rng.between(4, 4));
+        rng.between(0, u64::MAX);
+    }
+
+    #[test]
+    fn generated_inputs_parse() {
+        for day in crate::runner::DAYS {
+            for (seed, size) in [(0, 1), (1, 5), (2, DEFAULT_SIZE)] {
+                let input = (day.generate)(&mut Rng::new(seed), size);
+                if let Err(e) = (day.run)(&input, crate::runner::Part::Both) {
+                    panic!("Day {} with seed {seed}: {e}\n{input}", day.day);
+                }
+            }
+        }
+    }
+}
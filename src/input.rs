@@ -0,0 +1,91 @@
+//! Finding and reading puzzle inputs at runtime.
+
+use color_eyre::{eyre::WrapErr, Result};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+
+use crate::{parse, Solution};
+
+/// Environment variable naming the directory that holds the `dayN.txt` inputs.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// The directory named by [`INPUT_DIR_VAR`], or the current directory if it isn't set.
+pub fn input_dir() -> PathBuf {
+    std::env::var_os(INPUT_DIR_VAR).map_or_else(PathBuf::new, PathBuf::from)
+}
+
+/// Where to read a puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    Path(PathBuf),
+}
+
+impl Source {
+    /// Reads from stdin when `arg` is `-`, otherwise from the path given.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => Source::Stdin,
+            path => Source::Path(path.into()),
+        }
+    }
+
+    /// The input for `day` when none is given: `dayN.txt` in the [`input_dir`].
+    pub fn default_for(day: u8) -> Self {
+        Source::Path(input_dir().join(format!("day{day}.txt")))
+    }
+
+    pub fn read(&self) -> Result<String> {
+        match self {
+            Source::Stdin => {
+                let mut s = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut s)
+                    .wrap_err("Couldn't read the input from stdin")?;
+                Ok(s)
+            }
+            Source::Path(path) => std::fs::read_to_string(path)
+                .wrap_err_with(|| format!("Couldn't read the input file {}", path.display())),
+        }
+    }
+
+    /// Opens the input to read a bit at a time, for inputs too big to hold in memory.
+    pub fn reader(&self) -> Result<Box<dyn BufRead>> {
+        match self {
+            Source::Stdin => Ok(Box::new(std::io::stdin().lock())),
+            Source::Path(path) => {
+                let file = File::open(path)
+                    .wrap_err_with(|| format!("Couldn't open the input file {}", path.display()))?;
+                Ok(Box::new(BufReader::new(file)))
+            }
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "<stdin>"),
+            Source::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Where to read the input for `day` from: `arg` if given (see [`Source::from_arg`]), or the
+/// default location otherwise.
+pub fn source(day: u8, arg: Option<&str>) -> Source {
+    arg.map_or_else(|| Source::default_for(day), Source::from_arg)
+}
+
+/// Reads the input for `day` from [`source`].
+pub fn load(day: u8, arg: Option<&str>) -> Result<String> {
+    source(day, arg).read()
+}
+
+/// Reads and parses the input for `S` from [`source`], naming the input in any parse error.
+pub fn parse<S: Solution>(arg: Option<&str>) -> Result<S::Input> {
+    let source = source(S::DAY, arg);
+    S::parse(&source.read()?).map_err(|e| parse::name_input(e, &source.to_string()))
+}
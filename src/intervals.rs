@@ -0,0 +1,483 @@
+//! Sets of whole numbers kept as sorted ranges, such as the sections elves are assigned to clean,
+//! and an index for finding which of many ranges overlap.
+
+use std::fmt::Display;
+
+/// The numbers from `start` to `end`, inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Interval {
+    /// The numbers from `start` to `end`, or `None` if `start` is after `end`.
+    pub fn new(start: u64, end: u64) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    /// How many numbers there are, which can only be too many to count for `0..=u64::MAX`.
+    pub fn len(&self) -> u64 {
+        (self.end - self.start).saturating_add(1)
+    }
+
+    /// Never true, but here to go with [`Interval::len`].
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn contains(&self, n: u64) -> bool {
+        self.start <= n && n <= self.end
+    }
+
+    /// Whether every number in `other` is in this one.
+    pub fn covers(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Whether the two share any number, which is the same either way round.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of numbers, as the fewest intervals that hold them, in order. Intervals that overlap or
+/// touch are merged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The numbers from `start` to `end`, which is none of them if `start` is after `end`.
+    pub fn range(start: u64, end: u64) -> Self {
+        Interval::new(start, end).into_iter().collect()
+    }
+
+    /// The intervals, in order, none overlapping or touching another.
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// How many numbers are in the set.
+    pub fn coverage(&self) -> u64 {
+        self.intervals
+            .iter()
+            .fold(0, |total: u64, i| total.saturating_add(i.len()))
+    }
+
+    /// The interval from the smallest number to the largest.
+    pub fn span(&self) -> Option<Interval> {
+        let (first, last) = (self.intervals.first()?, self.intervals.last()?);
+        Interval::new(first.start, last.end)
+    }
+
+    /// The numbers missing between the smallest and the largest.
+    pub fn gaps(&self) -> IntervalSet {
+        let intervals = self
+            .intervals
+            .windows(2)
+            .filter_map(|w| Interval::new(w[0].end + 1, w[1].start - 1))
+            .collect();
+        IntervalSet { intervals }
+    }
+
+    pub fn contains(&self, n: u64) -> bool {
+        let idx = self.intervals.partition_point(|i| i.end < n);
+        self.intervals.get(idx).is_some_and(|i| i.contains(n))
+    }
+
+    /// The intervals in the set that share any number with `interval`.
+    pub fn overlapping(&self, interval: &Interval) -> &[Interval] {
+        let from = self.intervals.partition_point(|i| i.end < interval.start);
+        let to = self.intervals.partition_point(|i| i.start <= interval.end);
+        &self.intervals[from..to]
+    }
+
+    /// Adds the numbers in `interval`.
+    pub fn insert(&mut self, interval: Interval) {
+        // Everything that overlaps or touches the new interval is replaced by it, grown to cover
+        // them.
+        let from = self
+            .intervals
+            .partition_point(|i| i.end.saturating_add(1) < interval.start);
+        let to = self
+            .intervals
+            .partition_point(|i| i.start <= interval.end.saturating_add(1));
+        let merged = self.intervals[from..to]
+            .iter()
+            .fold(interval, |merged, i| Interval {
+                start: merged.start.min(i.start),
+                end: merged.end.max(i.end),
+            });
+        self.intervals.splice(from..to, [merged]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+        let mut intervals = Vec::new();
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            intervals.extend(x.intersection(y));
+            // Whichever ends first can't overlap anything else in the other set.
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The numbers in this set that aren't in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut others = other.intervals.iter().peekable();
+        for &interval in &self.intervals {
+            let mut rest = Some(interval);
+            while let (Some(left), Some(cut)) = (rest, others.peek()) {
+                if cut.end < left.start {
+                    others.next();
+                    continue;
+                }
+                if cut.start > left.end {
+                    break;
+                }
+                if cut.start > left.start {
+                    intervals.push(Interval {
+                        start: left.start,
+                        end: cut.start - 1,
+                    });
+                }
+                rest = cut
+                    .end
+                    .checked_add(1)
+                    .and_then(|start| Interval::new(start, left.end));
+                if cut.end <= left.end {
+                    others.next();
+                }
+            }
+            intervals.extend(rest);
+        }
+        IntervalSet { intervals }
+    }
+
+    /// Whether every number in `other` is in this set.
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.difference(self).is_empty()
+    }
+
+    /// Whether the two sets share any number, which is the same either way round.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    /// Merges the intervals into a set.
+    fn from_iter<T: IntoIterator<Item = Interval>>(iter: T) -> Self {
+        let mut intervals: Vec<_> = iter.into_iter().collect();
+        intervals.sort();
+        let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end.saturating_add(1) => {
+                    last.end = last.end.max(interval.end)
+                }
+                _ => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        IntervalSet {
+            intervals: vec![interval],
+        }
+    }
+}
+
+impl Display for IntervalSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, interval) in self.intervals.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{interval}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An interval in an [`IntervalTree`], with its value.
+pub type Entry<T> = (Interval, T);
+
+/// Intervals, each with a value, indexed to find the ones that overlap any other interval in
+/// `O(log n + k)` for `k` matches.
+///
+/// The entries are sorted by where they start, and make an implicit balanced tree: the middle
+/// entry of any slice of them is the root of that slice, with the halves either side as its
+/// children. Each root also keeps the latest end in its slice, to skip the slices that finish too
+/// early.
+#[derive(Debug, Clone)]
+pub struct IntervalTree<T> {
+    entries: Vec<Entry<T>>,
+    latest_end: Vec<u64>,
+}
+
+impl<T> IntervalTree<T> {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The entries, in order of where they start.
+    pub fn entries(&self) -> &[Entry<T>] {
+        &self.entries
+    }
+
+    /// The entries that contain `n`, in order of where they start.
+    pub fn containing(&self, n: u64) -> Vec<&Entry<T>> {
+        self.overlapping(&Interval { start: n, end: n })
+    }
+
+    /// The entries that share any number with `interval`, in order of where they start.
+    pub fn overlapping(&self, interval: &Interval) -> Vec<&Entry<T>> {
+        let mut found = Vec::new();
+        self.visit(0, self.entries.len(), interval, &mut found);
+        found
+    }
+
+    fn visit<'a>(
+        &'a self,
+        lo: usize,
+        hi: usize,
+        interval: &Interval,
+        found: &mut Vec<&'a Entry<T>>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.latest_end[mid] < interval.start {
+            return;
+        }
+        self.visit(lo, mid, interval, found);
+        let entry = &self.entries[mid];
+        // Everything after this starts later still.
+        if entry.0.start > interval.end {
+            return;
+        }
+        if entry.0.overlaps(interval) {
+            found.push(entry);
+        }
+        self.visit(mid + 1, hi, interval, found);
+    }
+
+    /// Every two entries that overlap each other, the one that starts first (or comes first in
+    /// the tree) first, in `O(n log n + k)` for `k` pairs.
+    pub fn overlapping_pairs(&self) -> Vec<(&Entry<T>, &Entry<T>)> {
+        let mut pairs = Vec::new();
+        for (idx, entry) in self.entries.iter().enumerate() {
+            // The entries that start after this one overlap it if they start before it ends.
+            let later = &self.entries[idx + 1..];
+            let overlap = later.partition_point(|(i, _)| i.start <= entry.0.end);
+            pairs.extend(later[..overlap].iter().map(|other| (entry, other)));
+        }
+        pairs
+    }
+
+    /// Works out the latest end in the slice `lo..hi` for its root, and every root below it.
+    fn index(&mut self, lo: usize, hi: usize) -> u64 {
+        if lo >= hi {
+            return 0;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let latest = (self.entries[mid].0.end)
+            .max(self.index(lo, mid))
+            .max(self.index(mid + 1, hi));
+        self.latest_end[mid] = latest;
+        latest
+    }
+}
+
+impl<T> FromIterator<Entry<T>> for IntervalTree<T> {
+    fn from_iter<I: IntoIterator<Item = Entry<T>>>(iter: I) -> Self {
+        let mut entries: Vec<_> = iter.into_iter().collect();
+        entries.sort_by_key(|(interval, _)| *interval);
+        let mut tree = IntervalTree {
+            latest_end: vec![0; entries.len()],
+            entries,
+        };
+        tree.index(0, tree.entries.len());
+        tree
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gen::Rng;
+
+    fn set(ranges: &[(u64, u64)]) -> IntervalSet {
+        ranges
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end).unwrap())
+            .collect()
+    }
+
+    /// The numbers in `set`, for checking against the obvious way on small sets.
+    fn numbers(set: &IntervalSet) -> Vec<u64> {
+        set.intervals()
+            .iter()
+            .flat_map(|i| i.start..=i.end)
+            .collect()
+    }
+
+    #[test]
+    fn merges_overlapping_and_touching() {
+        let merged = set(&[(5, 7), (1, 2), (3, 3), (6, 9), (12, 14)]);
+        assert_eq!("1-3, 5-9, 12-14", merged.to_string());
+        assert_eq!(11, merged.coverage());
+        assert_eq!(Interval::new(1, 14), merged.span());
+        assert_eq!("4-4, 10-11", merged.gaps().to_string());
+        assert!(merged.contains(6) && !merged.contains(4) && !merged.contains(15));
+        assert!(IntervalSet::range(7, 3).is_empty());
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(1, 5), (10, 20)]);
+        let b = set(&[(4, 12), (18, 30)]);
+        assert_eq!("1-30", a.union(&b).to_string());
+        assert_eq!("4-5, 10-12, 18-20", a.intersection(&b).to_string());
+        assert_eq!("1-3, 13-17", a.difference(&b).to_string());
+        assert_eq!("6-9, 21-30", b.difference(&a).to_string());
+        assert!(a.overlaps(&b) && b.overlaps(&a));
+        assert!(a.is_superset(&set(&[(2, 3), (15, 20)])));
+        assert!(!a.is_superset(&b));
+        let middle = Interval::new(4, 10).unwrap();
+        assert_eq!(a.intervals(), a.overlapping(&middle));
+        assert!(a.overlapping(&Interval::new(6, 9).unwrap()).is_empty());
+        let everything = IntervalSet::range(0, u64::MAX);
+        assert_eq!(u64::MAX, everything.coverage());
+        assert_eq!(
+            "0-0, 31-18446744073709551615",
+            everything.difference(&a.union(&b)).to_string()
+        );
+    }
+
+    #[test]
+    fn agrees_with_lists_of_numbers() {
+        let mut rng = Rng::new(4);
+        let random_set = |rng: &mut Rng| {
+            let count = rng.below(6);
+            (0..count)
+                .map(|_| {
+                    let start = rng.between(0, 40);
+                    Interval::new(start, start + rng.between(0, 8)).unwrap()
+                })
+                .collect::<IntervalSet>()
+        };
+        for _ in 0..500 {
+            let (a, b) = (random_set(&mut rng), random_set(&mut rng));
+            let (xs, ys) = (numbers(&a), numbers(&b));
+            let union: Vec<_> = (0..=50)
+                .filter(|n| xs.contains(n) || ys.contains(n))
+                .collect();
+            let both: Vec<_> = xs.iter().copied().filter(|n| ys.contains(n)).collect();
+            let only: Vec<_> = xs.iter().copied().filter(|n| !ys.contains(n)).collect();
+            assert_eq!(union, numbers(&a.union(&b)));
+            assert_eq!(both, numbers(&a.intersection(&b)));
+            assert_eq!(only, numbers(&a.difference(&b)));
+            assert_eq!(xs.len() as u64, a.coverage());
+            assert_eq!(!both.is_empty(), a.overlaps(&b));
+            for w in a.intervals().windows(2) {
+                assert!(w[0].end + 1 < w[1].start, "{a} isn't merged");
+            }
+            let mut inserted = a.clone();
+            b.intervals().iter().for_each(|&i| inserted.insert(i));
+            assert_eq!(a.union(&b), inserted);
+        }
+    }
+
+    #[test]
+    fn tree_finds_what_a_scan_does() {
+        let mut rng = Rng::new(24);
+        for size in [0, 1, 2, 7, 50] {
+            let entries: Vec<_> = (0..size)
+                .map(|idx| {
+                    let start = rng.between(0, 60);
+                    (
+                        Interval::new(start, start + rng.between(0, 15)).unwrap(),
+                        idx,
+                    )
+                })
+                .collect();
+            let tree: IntervalTree<_> = entries.iter().copied().collect();
+            assert_eq!(size, tree.len());
+            let values = |found: Vec<&(Interval, usize)>| {
+                let mut values: Vec<_> = found.iter().map(|(_, v)| *v).collect();
+                values.sort();
+                values
+            };
+            for _ in 0..50 {
+                let start = rng.between(0, 80);
+                let query = Interval::new(start, start + rng.between(0, 10)).unwrap();
+                let scanned: Vec<_> = entries
+                    .iter()
+                    .filter(|(i, _)| i.overlaps(&query))
+                    .map(|(_, v)| *v)
+                    .collect();
+                assert_eq!(scanned, values(tree.overlapping(&query)), "{query}");
+                let at = |n| entries.iter().filter(move |(i, _)| i.contains(n));
+                let scanned: Vec<_> = at(start).map(|(_, v)| *v).collect();
+                assert_eq!(scanned, values(tree.containing(start)));
+            }
+            let mut pairs: Vec<_> = tree
+                .overlapping_pairs()
+                .iter()
+                .map(|(a, b)| (a.1.min(b.1), a.1.max(b.1)))
+                .collect();
+            pairs.sort();
+            let scanned: Vec<_> = (0..size)
+                .flat_map(|a| (a + 1..size).map(move |b| (a, b)))
+                .filter(|&(a, b)| entries[a].0.overlaps(&entries[b].0))
+                .collect();
+            assert_eq!(scanned, pairs);
+        }
+    }
+}
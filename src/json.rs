@@ -0,0 +1,199 @@
+//! Just enough JSON to write out results for other tools.
+
+use color_eyre::Result;
+use std::fmt::{Display, Write};
+
+use crate::Solution;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys are written in the order they were added.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Starts an empty object, to be filled in with [`Json::with`].
+    pub fn object() -> Self {
+        Json::Object(Vec::new())
+    }
+
+    /// Adds `key` to this object.  Panics if this isn't an object.
+    pub fn with(mut self, key: &str, value: impl Into<Json>) -> Self {
+        match &mut self {
+            Json::Object(fields) => fields.push((key.to_owned(), value.into())),
+            _ => panic!("Can only add {key} to an object"),
+        }
+        self
+    }
+}
+
+fn write_str(f: &mut std::fmt::Formatter<'_>, s: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{b}"),
+            Json::Int(i) => write!(f, "{i}"),
+            Json::Float(x) if x.is_finite() => write!(f, "{x}"),
+            Json::Float(_) => f.write_str("null"),
+            Json::String(s) => write_str(f, s),
+            Json::Array(items) => {
+                f.write_char('[')?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(fields) => {
+                f.write_char('{')?;
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_str(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Json {
+            fn from(i: $t) -> Self {
+                Json::Int(i as i128)
+            }
+        })*
+    };
+}
+
+from_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl From<f64> for Json {
+    fn from(x: f64) -> Self {
+        Json::Float(x)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Json::Bool(b)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::String(s.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Json::String(s)
+    }
+}
+
+impl From<char> for Json {
+    fn from(c: char) -> Self {
+        Json::String(c.to_string())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(o: Option<T>) -> Self {
+        o.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(v: Vec<T>) -> Self {
+        Json::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Json>> From<Result<T>> for Json {
+    /// An object holding either the `answer` or the `error`.
+    fn from(r: Result<T>) -> Self {
+        match r {
+            Ok(answer) => Json::object().with("answer", answer),
+            Err(e) => Json::object().with("error", e.to_string()),
+        }
+    }
+}
+
+/// Solves both parts of the parsed `input`, reporting the answers and the day's details as
+/// `{"day":N,"part1":{"answer":...},"part2":{"answer":...},"details":{...}}`.
+///
+/// A part that fails has an `error` in place of its `answer`, as do the details.
+pub fn report<S: Solution>(input: &S::Input) -> Json {
+    let details = S::details(input).unwrap_or_else(|e| Json::object().with("error", e.to_string()));
+    Json::object()
+        .with("day", S::DAY)
+        .with("part1", S::part1(input))
+        .with("part2", S::part2(input))
+        .with("details", details)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn writes_json() {
+        let json = Json::object()
+            .with("day", 1u8)
+            .with("answers", vec!["24000", "45000"])
+            .with("missing", None::<u64>)
+            .with("mean", 1.5)
+            .with("ok", true);
+        assert_eq!(
+            r#"{"day":1,"answers":["24000","45000"],"missing":null,"mean":1.5,"ok":true}"#,
+            json.to_string()
+        );
+    }
+
+    #[test]
+    fn reports_answers_and_errors() {
+        use crate::day6::Day6;
+
+        let stream = Day6::parse("abcabcd").unwrap();
+        assert_eq!(
+            r#"{"day":6,"part1":{"answer":7},"part2":{"error":"No start-of-message marker found"},"details":{"packet_marker":"abcd","message_marker":null}}"#,
+            report::<Day6>(&stream).to_string()
+        );
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(
+            r#""a \"quote\"\n\\ \u0001""#,
+            Json::from("a \"quote\"\n\\ \u{1}").to_string()
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Solutions for [Advent of Code 2022](https://adventofcode.com/2022), usable as a library.
+//!
+//! Every day implements [`Solution`], which splits a puzzle into parsing the input once and then
+//! answering each of the two parts from the parsed input.
+
+use color_eyre::Result;
+use std::fmt::Display;
+
+use crate::json::Json;
+
+pub mod balance;
+pub mod bench;
+pub mod cli;
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod gen;
+pub mod input;
+pub mod intervals;
+pub mod json;
+pub mod log;
+pub mod parse;
+pub mod runner;
+pub mod verify;
+
+/// A solution to one day's puzzle.
+pub trait Solution {
+    /// The day of December this puzzle was released.
+    const DAY: u8;
+
+    /// The parsed puzzle input, shared by both parts.
+    type Input;
+    type Part1: Display + Into<Json>;
+    type Part2: Display + Into<Json>;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Result<Self::Part1>;
+    fn part2(input: &Self::Input) -> Result<Self::Part2>;
+
+    /// The intermediate results behind the answers, as a JSON object.
+    fn details(_input: &Self::Input) -> Result<Json> {
+        Ok(Json::object())
+    }
+}
@@ -0,0 +1,104 @@
+//! Diagnostic output, only shown when asked for.
+//!
+//! Answers go to stdout, and everything else goes to stderr through [`info!`](crate::info) unless
+//! quiet, or [`debug!`](crate::debug) and [`trace!`](crate::trace) once the level is turned up with
+//! [`set_level`].
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much to print, from least to most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Only the answers themselves.
+    Quiet,
+    /// The answers, explained.
+    Normal,
+    /// How we got to the answers.
+    Debug,
+    /// Every step along the way.
+    Trace,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Normal as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => Level::Quiet,
+        1 => Level::Normal,
+        2 => Level::Debug,
+        _ => Level::Trace,
+    }
+}
+
+/// Whether messages at `level` are being shown.
+pub fn enabled(level: Level) -> bool {
+    level <= self::level()
+}
+
+/// The level asked for with `-q` or some number of `-v`s.
+pub fn level_from_flags(quiet: bool, verbose: usize) -> Level {
+    match (quiet, verbose) {
+        (true, _) => Level::Quiet,
+        (false, 0) => Level::Normal,
+        (false, 1) => Level::Debug,
+        (false, _) => Level::Trace,
+    }
+}
+
+/// Prints an answer, as just `value` when quiet, or as `message` otherwise.
+pub fn answer(value: impl Display, message: impl Display) {
+    if enabled(Level::Normal) {
+        println!("{message}");
+    } else {
+        println!("{value}");
+    }
+}
+
+/// Prints to stderr unless running with `-q`.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Normal) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Prints to stderr if running with `-v` or more.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Prints to stderr if running with `-vv`.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Trace) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn levels_from_flags() {
+        assert_eq!(Level::Quiet, level_from_flags(true, 2));
+        assert_eq!(Level::Normal, level_from_flags(false, 0));
+        assert_eq!(Level::Debug, level_from_flags(false, 1));
+        assert_eq!(Level::Trace, level_from_flags(false, 3));
+        assert!(Level::Quiet < Level::Normal && Level::Debug < Level::Trace);
+    }
+}
@@ -0,0 +1,237 @@
+//! Errors that point at the place in the input that couldn't be parsed.
+
+use color_eyre::Report;
+use std::fmt::Display;
+
+/// A problem with the puzzle input, and where it is.
+///
+/// Errors are usually made while looking at part of a line, then placed on the whole line with
+/// [`ParseError::within`] and [`ParseError::on_line`] as they are returned to the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Name of the input, usually the file it was read from.
+    pub input: Option<String>,
+    /// Line number of `text` in the input, starting from 1.
+    pub line: Option<usize>,
+    /// Column of the start of the problem in `text`, starting from 1.
+    pub column: usize,
+    /// Number of characters that are part of the problem.
+    pub len: usize,
+    /// The text that contains the problem.
+    pub text: String,
+}
+
+/// Byte offset of `inner` in `outer`, if `inner` is a slice of `outer`.
+fn offset_in(outer: &str, inner: &str) -> Option<usize> {
+    let start = (inner.as_ptr() as usize).checked_sub(outer.as_ptr() as usize)?;
+    (start + inner.len() <= outer.len()).then_some(start)
+}
+
+impl ParseError {
+    /// A problem with all of `text`.
+    pub fn new(message: impl Into<String>, text: &str) -> Self {
+        Self {
+            message: message.into(),
+            input: None,
+            line: None,
+            column: 1,
+            len: text.chars().count().max(1),
+            text: text.to_owned(),
+        }
+    }
+
+    /// A problem with `span`, which is a slice of `text`.  An empty `span` points just after the
+    /// text before it, which is useful for saying something is missing.
+    pub fn at(message: impl Into<String>, text: &str, span: &str) -> Self {
+        let mut err = Self::new(message, text);
+        if let Some(start) = offset_in(text, span) {
+            err.column = text[..start].chars().count() + 1;
+            err.len = span.chars().count().max(1);
+        }
+        err
+    }
+
+    /// Moves the error into `outer`, with our text starting at byte `start`, or wherever our text
+    /// first appears in `outer` if that isn't known.
+    fn moved_to(mut self, outer: &str, start: Option<usize>) -> Self {
+        if let Some(start) = start.or_else(|| outer.find(&self.text)) {
+            self.column += outer[..start].chars().count();
+            self.text = outer.to_owned();
+        }
+        self
+    }
+
+    /// Moves an error made while parsing `inner` so it points into `outer`, which contains it.
+    pub fn within(self, outer: &str, inner: &str) -> Self {
+        let start = offset_in(outer, inner);
+        self.moved_to(outer, start)
+    }
+
+    /// Records that the error was on line `line` (starting from 1) of the input, which is `text`.
+    pub fn on_line(self, line: usize, text: &str) -> Self {
+        let mut err = if self.text == text {
+            self
+        } else {
+            self.moved_to(text, None)
+        };
+        err.line = Some(line);
+        err
+    }
+
+    /// Records the name of the input the error is in.
+    pub fn in_input(mut self, name: impl Into<String>) -> Self {
+        self.input = Some(name.into());
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let line_num = self.line.map(|l| l.to_string()).unwrap_or_default();
+        let gutter = " ".repeat(line_num.len());
+        writeln!(f, "{}", self.message)?;
+        write!(
+            f,
+            "{gutter}--> {}",
+            self.input.as_deref().unwrap_or("<input>")
+        )?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        writeln!(f, ":{}", self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line_num} | {}", self.text)?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.len)
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Every problem found in an input, for parsers that carry on past the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrors(pub Vec<ParseError>);
+
+impl Display for ParseErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, err) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "\n\n")?;
+            }
+            write!(f, "{err}")?;
+        }
+        if self.0.len() > 1 {
+            write!(f, "\n\n{} problems found", self.0.len())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseErrors {}
+
+impl From<ParseError> for ParseErrors {
+    fn from(err: ParseError) -> Self {
+        ParseErrors(vec![err])
+    }
+}
+
+/// Parses each line of `s` with `f`, placing any error on the line it came from.
+pub fn parse_lines<T>(
+    s: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    parse_numbered_lines(s, |_, line| f(line))
+}
+
+/// Like [`parse_lines`], but also passes `f` the line number, counting from 1.
+pub fn parse_numbered_lines<T>(
+    s: &str,
+    mut f: impl FnMut(usize, &str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    s.lines()
+        .enumerate()
+        .map(|(idx, line)| f(idx + 1, line).map_err(|e| e.on_line(idx + 1, line)))
+        .collect()
+}
+
+/// Parses `s` as a `T`, reporting `message` at `s` if it isn't one.
+pub fn number<T: std::str::FromStr>(s: &str, message: &str) -> Result<T, ParseError> {
+    s.parse().map_err(|_| ParseError::new(message, s))
+}
+
+/// Names the input in `report` if it is a [`ParseError`] or [`ParseErrors`].
+pub fn name_input(mut report: Report, name: &str) -> Report {
+    if let Some(err) = report.downcast_mut::<ParseError>() {
+        err.input = Some(name.to_owned());
+    }
+    if let Some(errs) = report.downcast_mut::<ParseErrors>() {
+        for err in &mut errs.0 {
+            err.input = Some(name.to_owned());
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn points_at_span() {
+        let line = "move 1 from x to 3";
+        let err = ParseError::at("Not a stack", line, &line[12..13]).on_line(4, line);
+        assert_eq!(13, err.column);
+        assert_eq!(1, err.len);
+        assert_eq!(
+            "Not a stack
+ --> <input>:4:13
+  |
+4 | move 1 from x to 3
+  |             ^",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn within_moves_to_outer_text() {
+        let line = "2-4,7-x";
+        let (_, second) = line.split_once(',').unwrap();
+        let err = ParseError::at("Not a number", second, &second[2..]).within(line, second);
+        assert_eq!(line, err.text);
+        assert_eq!(7, err.column);
+
+        // Copied text is found by searching for it.
+        let copied = second.to_owned();
+        let err = ParseError::new("Bad range", &copied).within(line, &copied);
+        assert_eq!(5, err.column);
+        assert_eq!(3, err.len);
+    }
+
+    #[test]
+    fn lists_every_error() {
+        let errs = ParseErrors(vec![
+            ParseError::new("First", "a").on_line(1, "a"),
+            ParseError::new("Second", "b").on_line(3, "b"),
+        ]);
+        let report = name_input(errs.into(), "in.txt");
+        let shown = report.to_string();
+        assert!(shown.starts_with("First\n --> in.txt:1:1"));
+        assert!(shown.contains("\n\nSecond\n --> in.txt:3:1"));
+        assert!(shown.ends_with("2 problems found"));
+    }
+
+    #[test]
+    fn parse_lines_sets_line_numbers() {
+        let err = parse_lines("1\n2\nx\n4", |l| number::<u32>(l, "Not a number"))
+            .unwrap_err()
+            .in_input("day1.txt");
+        assert_eq!(Some(3), err.line);
+        assert_eq!(Some("day1.txt".to_owned()), err.input);
+        assert!(err.to_string().contains("--> day1.txt:3:1"));
+    }
+}
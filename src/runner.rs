@@ -0,0 +1,132 @@
+//! Running any day's [`Solution`] by number, timing each step.
+
+use color_eyre::{eyre::eyre, Report, Result};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::gen::{Generator, Rng};
+use crate::{day1, day10, day2, day3, day4, day5, day6, day7, day8, day9, Solution};
+
+/// Which parts of a puzzle to solve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+    Both,
+}
+
+impl Part {
+    pub fn includes_one(&self) -> bool {
+        matches!(self, Part::One | Part::Both)
+    }
+
+    pub fn includes_two(&self) -> bool {
+        matches!(self, Part::Two | Part::Both)
+    }
+}
+
+impl FromStr for Part {
+    type Err = Report;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            "both" | "all" => Ok(Part::Both),
+            _ => Err(eyre!("Part must be 1, 2 or both, not {s}")),
+        }
+    }
+}
+
+/// The answer to one part and how long it took to find it.
+pub struct PartRun {
+    pub answer: Result<String>,
+    pub time: Duration,
+}
+
+/// The outcome of solving one day's puzzle.
+pub struct Run {
+    pub day: u8,
+    pub parse_time: Duration,
+    pub part1: Option<PartRun>,
+    pub part2: Option<PartRun>,
+}
+
+impl Run {
+    /// Total time spent parsing and solving.
+    pub fn total_time(&self) -> Duration {
+        self.parse_time
+            + self.part1.as_ref().map_or(Duration::ZERO, |p| p.time)
+            + self.part2.as_ref().map_or(Duration::ZERO, |p| p.time)
+    }
+}
+
+fn time_part<T: Display>(f: impl FnOnce() -> Result<T>) -> PartRun {
+    let start = Instant::now();
+    let answer = f().map(|a| a.to_string());
+    PartRun {
+        answer,
+        time: start.elapsed(),
+    }
+}
+
+/// Parses `input` and solves the requested parts with the solution `S`.
+///
+/// Fails only if the input can't be parsed; a part that fails is reported in its [`PartRun`].
+pub fn run<S: Solution>(input: &str, part: Part) -> Result<Run> {
+    let start = Instant::now();
+    let parsed = S::parse(input)?;
+    let parse_time = start.elapsed();
+
+    let part1 = part.includes_one().then(|| time_part(|| S::part1(&parsed)));
+    let part2 = part.includes_two().then(|| time_part(|| S::part2(&parsed)));
+
+    Ok(Run {
+        day: S::DAY,
+        parse_time,
+        part1,
+        part2,
+    })
+}
+
+/// A day that has a solution, with its solver erased so all days can be listed together.
+pub struct Day {
+    pub day: u8,
+    pub run: fn(&str, Part) -> Result<Run>,
+    /// See [`Generator::generate`].
+    pub generate: fn(&mut Rng, usize) -> String,
+    /// See [`Generator::reference`].
+    pub reference: fn(&str) -> [Option<String>; 2],
+}
+
+impl Day {
+    const fn of<S: Generator>() -> Self {
+        Self {
+            day: S::DAY,
+            run: run::<S>,
+            generate: S::generate,
+            reference: S::reference,
+        }
+    }
+}
+
+/// Every day with a solution, in order.
+pub const DAYS: &[Day] = &[
+    Day::of::<day1::Day1>(),
+    Day::of::<day2::Day2>(),
+    Day::of::<day3::Day3>(),
+    Day::of::<day4::Day4>(),
+    Day::of::<day5::Day5>(),
+    Day::of::<day6::Day6>(),
+    Day::of::<day7::Day7>(),
+    Day::of::<day8::Day8>(),
+    Day::of::<day9::Day9>(),
+    Day::of::<day10::Day10>(),
+];
+
+/// Finds the solution for `day`.
+pub fn find(day: u8) -> Result<&'static Day> {
+    DAYS.iter()
+        .find(|d| d.day == day)
+        .ok_or(eyre!("There is no solution for day {day}"))
+}
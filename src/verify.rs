@@ -0,0 +1,323 @@
+//! Checking every day's answers against a file of known answers.
+//!
+//! An answers file has one answer per line, written as `dayN partN INPUT = ANSWER`, where INPUT
+//! is a path relative to the answers file. Answers that span several lines (like the CRT picture
+//! from day 10) leave the text after `=` empty and continue on the following lines, each prefixed
+//! by `|`. Blank lines and lines starting with `#` are ignored.
+
+use color_eyre::{eyre::eyre, eyre::WrapErr, Result};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use crate::input::Source;
+use crate::parse;
+use crate::runner::{self, Part};
+
+/// The answer we expect for one part of a day with a particular input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub day: u8,
+    pub part: u8,
+    pub input: PathBuf,
+    pub answer: String,
+}
+
+impl Display for Expected {
+    /// Writes the line, or lines, of an answers file giving this answer.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day{} part{} {} =",
+            self.day,
+            self.part,
+            self.input.display()
+        )?;
+        if self.answer.contains('\n') {
+            for line in self.answer.lines() {
+                write!(f, "\n|{line}")?;
+            }
+            Ok(())
+        } else {
+            write!(f, " {}", self.answer)
+        }
+    }
+}
+
+fn parse_header(line: &str, base: &Path) -> Result<(Expected, bool)> {
+    let (key, answer) = line
+        .split_once('=')
+        .ok_or(eyre!("Missing '=' between the input and the answer"))?;
+    let mut tokens = key.split_whitespace();
+    let day = tokens
+        .next()
+        .and_then(|t| t.strip_prefix("day"))
+        .ok_or(eyre!("Expected dayN to start the line"))?
+        .parse()?;
+    let part = match tokens.next().and_then(|t| t.strip_prefix("part")) {
+        Some("1") => 1,
+        Some("2") => 2,
+        _ => return Err(eyre!("Expected part1 or part2 after the day")),
+    };
+    let input = base.join(tokens.next().ok_or(eyre!("Missing the input file"))?);
+    if let Some(extra) = tokens.next() {
+        return Err(eyre!("Unexpected {extra} before '='"));
+    }
+    let answer = answer.trim();
+    Ok((
+        Expected {
+            day,
+            part,
+            input,
+            answer: answer.to_owned(),
+        },
+        answer.is_empty(),
+    ))
+}
+
+/// Parses the answers file contents, with inputs relative to `base`.
+pub fn parse_answers(s: &str, base: &Path) -> Result<Vec<Expected>> {
+    let mut answers: Vec<Expected> = Vec::new();
+    let mut multiline = false;
+    for (idx, line) in s.lines().enumerate() {
+        if let Some(rest) = line.strip_prefix('|') {
+            let last = answers
+                .last_mut()
+                .filter(|_| multiline)
+                .ok_or(eyre!("Line {}: '|' continues a multi-line answer", idx + 1))?;
+            if !last.answer.is_empty() {
+                last.answer.push('\n');
+            }
+            last.answer.push_str(rest);
+            continue;
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let (expected, continues) =
+            parse_header(trimmed, base).wrap_err_with(|| format!("Line {}: {line}", idx + 1))?;
+        multiline = continues;
+        answers.push(expected);
+    }
+    Ok(answers)
+}
+
+/// Reads the answers file at `path`.
+pub fn load_answers(path: &Path) -> Result<Vec<Expected>> {
+    let s = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Couldn't read the answers file {}", path.display()))?;
+    parse_answers(&s, path.parent().unwrap_or(Path::new("")))
+}
+
+/// How a solution did compared to the expected answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// The input couldn't be read or solved.
+    Error(String),
+    /// There is no expected answer for this part.
+    Missing,
+}
+
+/// The result of checking one part of a day.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub input: Option<PathBuf>,
+    pub outcome: Outcome,
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day{} part{}", self.day, self.part)?;
+        if let Some(input) = &self.input {
+            write!(f, " {}", input.display())?;
+        }
+        match &self.outcome {
+            Outcome::Pass => write!(f, ": pass"),
+            Outcome::Fail { expected, actual } => {
+                write!(f, ": FAIL\n{}", diff(expected, actual))
+            }
+            Outcome::Error(e) => write!(f, ": ERROR {e}"),
+            Outcome::Missing => write!(f, ": missing"),
+        }
+    }
+}
+
+/// Shows the lines that differ between the two answers, prefixed by `-` for the expected line and
+/// `+` for the actual one.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    let mut out = Vec::new();
+    for idx in 0..expected.len().max(actual.len()) {
+        let (e, a) = (expected.get(idx), actual.get(idx));
+        if e == a {
+            out.push(format!("  {}", e.unwrap()));
+            continue;
+        }
+        if let Some(e) = e {
+            out.push(format!("- {e}"));
+        }
+        if let Some(a) = a {
+            out.push(format!("+ {a}"));
+        }
+    }
+    out.join("\n")
+}
+
+fn check_answer(expected: &Expected, actual: &Option<runner::PartRun>) -> Outcome {
+    match actual.as_ref().map(|p| &p.answer) {
+        Some(Ok(actual)) if actual.trim_end() == expected.answer.trim_end() => Outcome::Pass,
+        Some(Ok(actual)) => Outcome::Fail {
+            expected: expected.answer.clone(),
+            actual: actual.clone(),
+        },
+        Some(Err(e)) => Outcome::Error(e.to_string()),
+        None => Outcome::Missing,
+    }
+}
+
+/// Solves every input in `answers` and compares the results, then lists the parts of every day
+/// that have no expected answer at all.
+pub fn verify(answers: &[Expected]) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    // Solve each input once, checking all of the answers expected for it.
+    let mut done: Vec<(u8, &Path)> = Vec::new();
+    for expected in answers {
+        let key = (expected.day, expected.input.as_path());
+        if done.contains(&key) {
+            continue;
+        }
+        done.push(key);
+
+        let for_input: Vec<_> = answers
+            .iter()
+            .filter(|a| (a.day, a.input.as_path()) == key)
+            .collect();
+        let source = Source::Path(expected.input.clone());
+        let run = runner::find(expected.day).and_then(|day| {
+            (day.run)(&source.read()?, Part::Both)
+                .map_err(|e| parse::name_input(e, &source.to_string()))
+        });
+        for expected in for_input {
+            let outcome = match &run {
+                Ok(run) if expected.part == 1 => check_answer(expected, &run.part1),
+                Ok(run) => check_answer(expected, &run.part2),
+                Err(e) => Outcome::Error(e.to_string()),
+            };
+            checks.push(Check {
+                day: expected.day,
+                part: expected.part,
+                input: Some(expected.input.clone()),
+                outcome,
+            });
+        }
+    }
+
+    for day in runner::DAYS {
+        for part in 1..=2 {
+            if !answers.iter().any(|a| a.day == day.day && a.part == part) {
+                checks.push(Check {
+                    day: day.day,
+                    part,
+                    input: None,
+                    outcome: Outcome::Missing,
+                });
+            }
+        }
+    }
+
+    checks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_answers() {
+        let answers = parse_answers(
+            "# comment
+day1 part1 day1.txt = 24000
+
+day10 part2 in/day10.txt =
+|##..
+|..##
+day5 part2 day5.txt = MCD",
+            Path::new("base"),
+        )
+        .unwrap();
+
+        assert_eq!(3, answers.len());
+        assert_eq!(
+            Expected {
+                day: 1,
+                part: 1,
+                input: PathBuf::from("base/day1.txt"),
+                answer: "24000".to_owned(),
+            },
+            answers[0]
+        );
+        assert_eq!("##..\n..##", answers[1].answer);
+        assert_eq!(PathBuf::from("base/in/day10.txt"), answers[1].input);
+        assert_eq!("MCD", answers[2].answer);
+    }
+
+    #[test]
+    fn writes_answers_that_parse_back() {
+        let answers = vec![
+            Expected {
+                day: 1,
+                part: 2,
+                input: PathBuf::from("day1.txt"),
+                answer: "45000".to_owned(),
+            },
+            Expected {
+                day: 10,
+                part: 2,
+                input: PathBuf::from("day10.txt"),
+                answer: "##..\n..##".to_owned(),
+            },
+        ];
+        let written: Vec<_> = answers.iter().map(Expected::to_string).collect();
+        assert_eq!("day1 part2 day1.txt = 45000", written[0]);
+        assert_eq!(
+            answers,
+            parse_answers(&written.join("\n"), Path::new("")).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_bad_answers() {
+        assert!(parse_answers("day1 part3 day1.txt = 1", Path::new("")).is_err());
+        assert!(parse_answers("day1 part1 day1.txt 1", Path::new("")).is_err());
+        assert!(parse_answers("|##..", Path::new("")).is_err());
+        assert!(parse_answers("day1 part1 day1.txt = 1\n|2", Path::new("")).is_err());
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        assert_eq!("  ##\n- ..\n+ .#", diff("##\n..", "##\n.#"));
+        assert_eq!("- 1\n+ 2", diff("1", "2"));
+    }
+
+    #[test]
+    fn examples_pass() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs/examples/answers.txt");
+        let checks = verify(&load_answers(&path).unwrap());
+        for check in &checks {
+            assert!(
+                matches!(check.outcome, Outcome::Pass | Outcome::Missing),
+                "{check}"
+            );
+        }
+        assert!(checks.iter().any(|c| c.outcome == Outcome::Pass));
+    }
+}